@@ -48,10 +48,12 @@ document.getElementById('calculate').addEventListener('click', () => {
                 const foundIndex = requests.findIndex(r => r.i === idx);
                 if (foundIndex === -1) return [orig, null, null, null, null, null, null];
                 const r = results[foundIndex];
+                if (r.error) return [parseFloat(orig), null, null, null, null, null, null];
                 const invBg = r.bg !== 0 ? 1 / r.bg : null;
                 return [parseFloat(orig), r.pwbs, r.z, r.p_over_z, invBg, r.niandu, r.cg];
             });
             HotUtils.content().loadData(newData);
+            HotUtils.reportRowErrors(requests, results);
         })
         .catch(err => { console.error(err); alert('批量计算出错'); });
 });
\ No newline at end of file
@@ -44,9 +44,11 @@ document.getElementById('calculate').addEventListener('click', () => {
                 const foundIndex = requests.findIndex(r => r.i === idx);
                 if (foundIndex === -1) return [orig, null, null, null, null, null, null];
                 const r = results[foundIndex];
+                if (r.error) return [parseFloat(orig), null, null, null, null, null, null];
                 return [parseFloat(orig), r.z, r.p_over_z, r.bg, r.niandu, r.cg, r.density];
             });
             HotUtils.content().loadData(newData);
+            HotUtils.reportRowErrors(requests, results);
         })
         .catch(err => { console.error(err); alert('批量计算出错'); });
 });
\ No newline at end of file
@@ -194,10 +194,24 @@ function handleBatchResults(pressuresRaw, requests, results, resultMapper) {
             return [orig, null, null, null, null, null, null];
         }
         const r = results[foundIndex];
+        if (r.error) {
+            return [parseFloat(orig), null, null, null, null, null, null];
+        }
         return resultMapper(orig, r);
     });
 
     content.loadData(newData);
+    reportRowErrors(requests, results);
+}
+
+// 提示批量计算中失败的行及原因
+function reportRowErrors(requests, results) {
+    const messages = results
+        .map((r, k) => (r && r.error ? `第 ${requests[k].i + 1} 行：${r.error.message}` : null))
+        .filter(m => m !== null);
+    if (messages.length > 0) {
+        alert('以下行计算失败：\n' + messages.join('\n'));
+    }
 }
 
 // 检查表格是否已初始化
@@ -219,5 +233,6 @@ window.HotUtils = {
     getPressuresFromTable,
     validateWellInfoParams,
     handleBatchResults,
+    reportRowErrors,
     checkTablesInitialized
 };
\ No newline at end of file
@@ -0,0 +1,657 @@
+use crate::composition::{ComponentId, Mixture};
+use crate::solver::{Newton, Solution};
+use serde::{Deserialize, Serialize};
+
+/// 通用气体常数，J/(mol·K)
+const R: f64 = 8.31451;
+
+/// AGA8 计算方法
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Aga8Method {
+    /// 详细特征法（AGA8-92DC，GB/T 17747.2），需完整组分分析
+    #[default]
+    Detail,
+    /// 物性值法（SGERG-88，GB/T 17747.3），需相对密度、N2、CO2
+    Gross,
+}
+
+// ============ 详细特征法（AGA8-92DC） ============
+// 组分顺序：C1 N2 CO2 C2 C3 iC4 nC4 iC5 nC5 C6 C7 C8 C9 C10 H2 O2 CO H2O H2S He Ar
+const NC: usize = 21;
+
+const MW: [f64; NC] = [
+    16.043, 28.0135, 44.01, 30.07, 44.097, 58.123, 58.123, 72.15, 72.15, 86.177, 100.204, 114.231,
+    128.258, 142.285, 2.0159, 31.9988, 28.01, 18.0153, 34.082, 4.0026, 39.948,
+];
+
+/// 特征能量参数 Ei，K
+const EI: [f64; NC] = [
+    151.3183, 99.73778, 241.9606, 244.1667, 298.1183, 324.0689, 337.6389, 365.5999, 370.6823,
+    402.636293, 427.72263, 450.325022, 470.840891, 489.558373, 26.95794, 122.7667, 105.5348,
+    514.0156, 296.355, 2.610111, 119.6299,
+];
+
+/// 尺寸参数 Ki，(m³/kmol)^(1/3)
+const KI: [f64; NC] = [
+    0.4619255, 0.4479153, 0.4557489, 0.5279209, 0.583749, 0.6406937, 0.6341423, 0.6738577,
+    0.6798307, 0.7175118, 0.7525189, 0.784955, 0.8152731, 0.8437826, 0.3514916, 0.4186954,
+    0.4533894, 0.3825868, 0.4618263, 0.3589888, 0.4216551,
+];
+
+/// 定向参数 Gi
+const GI: [f64; NC] = [
+    0.0, 0.027815, 0.189065, 0.0793, 0.141239, 0.256692, 0.281835, 0.332267, 0.366911, 0.289731,
+    0.337542, 0.383381, 0.427354, 0.469659, 0.034369, 0.021, 0.038953, 0.3325, 0.0885, 0.0, 0.0,
+];
+
+/// 四极参数 Qi（CO2、H2O、H2S）
+const QI: [f64; NC] = {
+    let mut q = [0.0; NC];
+    q[2] = 0.69;
+    q[17] = 1.06775;
+    q[18] = 0.633276;
+    q
+};
+
+/// 高温参数 Fi（H2）
+const FI: [f64; NC] = {
+    let mut f = [0.0; NC];
+    f[14] = 1.0;
+    f
+};
+
+/// 偶极参数 Si（H2O、H2S）
+const SI: [f64; NC] = {
+    let mut s = [0.0; NC];
+    s[17] = 1.5822;
+    s[18] = 0.39;
+    s
+};
+
+/// 缔合参数 Wi（H2O）
+const WI: [f64; NC] = {
+    let mut w = [0.0; NC];
+    w[17] = 1.0;
+    w
+};
+
+/// 状态方程系数项：(an, bn, cn, kn, un, gn, qn, fn, sn, wn)
+type Term = (f64, i32, f64, i32, f64, u8, u8, u8, u8, u8);
+
+/// 状态方程系数（AGA8 表 4）
+#[rustfmt::skip]
+const TERMS: [Term; 58] = [
+    (0.1538326, 1, 0.0, 0, 0.0, 0, 0, 0, 0, 0),
+    (1.341953, 1, 0.0, 0, 0.5, 0, 0, 0, 0, 0),
+    (-2.998583, 1, 0.0, 0, 1.0, 0, 0, 0, 0, 0),
+    (-0.04831228, 1, 0.0, 0, 3.5, 0, 0, 0, 0, 0),
+    (0.3757965, 1, 0.0, 0, -0.5, 1, 0, 0, 0, 0),
+    (-1.589575, 1, 0.0, 0, 4.5, 1, 0, 0, 0, 0),
+    (-0.05358847, 1, 0.0, 0, 0.5, 0, 1, 0, 0, 0),
+    (0.88659463, 1, 0.0, 0, 7.5, 0, 0, 0, 1, 0),
+    (-0.71023704, 1, 0.0, 0, 9.5, 0, 0, 0, 1, 0),
+    (-1.471722, 1, 0.0, 0, 6.0, 0, 0, 0, 0, 1),
+    (1.32185035, 1, 0.0, 0, 12.0, 0, 0, 0, 0, 1),
+    (-0.78665925, 1, 0.0, 0, 12.5, 0, 0, 0, 0, 1),
+    (2.29129e-9, 1, 1.0, 3, -6.0, 0, 0, 1, 0, 0),
+    (0.1576724, 1, 1.0, 2, 2.0, 0, 0, 0, 0, 0),
+    (-0.4363864, 1, 1.0, 2, 3.0, 0, 0, 0, 0, 0),
+    (-0.04408159, 1, 1.0, 2, 2.0, 0, 1, 0, 0, 0),
+    (-0.003433888, 1, 1.0, 4, 2.0, 0, 0, 0, 0, 0),
+    (0.03205905, 1, 1.0, 4, 11.0, 0, 0, 0, 0, 0),
+    (0.02487355, 2, 0.0, 0, -0.5, 0, 0, 0, 0, 0),
+    (0.07332279, 2, 0.0, 0, 0.5, 0, 0, 0, 0, 0),
+    (-0.001600573, 2, 1.0, 2, 0.0, 0, 0, 0, 0, 0),
+    (0.6424706, 2, 1.0, 2, 4.0, 0, 0, 0, 0, 0),
+    (-0.4162601, 2, 1.0, 2, 6.0, 0, 0, 0, 0, 0),
+    (-0.06689957, 2, 1.0, 4, 21.0, 0, 0, 0, 0, 0),
+    (0.2791795, 2, 1.0, 4, 23.0, 1, 0, 0, 0, 0),
+    (-0.6966051, 2, 1.0, 4, 22.0, 0, 1, 0, 0, 0),
+    (-0.002860589, 2, 1.0, 4, -1.0, 0, 0, 1, 0, 0),
+    (-0.008098836, 3, 0.0, 0, -0.5, 0, 1, 0, 0, 0),
+    (3.150547, 3, 1.0, 1, 7.0, 1, 0, 0, 0, 0),
+    (0.007224479, 3, 1.0, 1, -1.0, 0, 0, 1, 0, 0),
+    (-0.7057529, 3, 1.0, 2, 6.0, 0, 0, 0, 0, 0),
+    (0.5349792, 3, 1.0, 2, 4.0, 1, 0, 0, 0, 0),
+    (-0.07931491, 3, 1.0, 3, 1.0, 1, 0, 0, 0, 0),
+    (-1.418465, 3, 1.0, 3, 9.0, 1, 0, 0, 0, 0),
+    (-5.99905e-17, 3, 1.0, 4, -13.0, 0, 0, 1, 0, 0),
+    (0.1058402, 3, 1.0, 4, 21.0, 0, 0, 0, 0, 0),
+    (0.03431729, 3, 1.0, 4, 8.0, 0, 1, 0, 0, 0),
+    (-0.007022847, 4, 0.0, 0, -0.5, 0, 0, 0, 0, 0),
+    (0.02495587, 4, 0.0, 0, 0.0, 0, 0, 0, 0, 0),
+    (0.04296818, 4, 1.0, 2, 2.0, 0, 0, 0, 0, 0),
+    (0.7465453, 4, 1.0, 2, 7.0, 0, 0, 0, 0, 0),
+    (-0.2919613, 4, 1.0, 2, 9.0, 0, 1, 0, 0, 0),
+    (7.294616, 4, 1.0, 4, 22.0, 0, 0, 0, 0, 0),
+    (-9.936757, 4, 1.0, 4, 23.0, 0, 0, 0, 0, 0),
+    (-0.005399808, 5, 0.0, 0, 1.0, 0, 0, 0, 0, 0),
+    (-0.2432567, 5, 1.0, 2, 9.0, 0, 0, 0, 0, 0),
+    (0.04987016, 5, 1.0, 2, 3.0, 0, 1, 0, 0, 0),
+    (0.003733797, 5, 1.0, 4, 8.0, 0, 0, 0, 0, 0),
+    (1.874951, 5, 1.0, 4, 23.0, 0, 1, 0, 0, 0),
+    (0.002168144, 6, 0.0, 0, 1.5, 0, 0, 0, 0, 0),
+    (-0.6587164, 6, 1.0, 2, 5.0, 1, 0, 0, 0, 0),
+    (0.000205518, 7, 0.0, 0, -0.5, 0, 1, 0, 0, 0),
+    (0.009776195, 7, 1.0, 2, 4.0, 0, 0, 0, 0, 0),
+    (-0.02048708, 8, 1.0, 1, 7.0, 1, 0, 0, 0, 0),
+    (0.01557322, 8, 1.0, 2, 3.0, 0, 0, 0, 0, 0),
+    (0.006862415, 8, 1.0, 2, 0.0, 1, 0, 0, 0, 0),
+    (-0.001226752, 9, 1.0, 2, 1.0, 0, 0, 0, 0, 0),
+    (0.002850908, 9, 1.0, 2, 0.0, 0, 1, 0, 0, 0),
+];
+
+/// 二元能量参数 E*ij（未列出者为 1）
+#[rustfmt::skip]
+const EIJ: [(usize, usize, f64); 59] = [
+    (0, 1, 0.97164), (0, 2, 0.960644), (0, 4, 0.994635), (0, 5, 1.01953), (0, 6, 0.989844),
+    (0, 7, 1.00235), (0, 8, 0.999268), (0, 9, 1.107274), (0, 10, 0.88088), (0, 11, 0.880973),
+    (0, 12, 0.881067), (0, 13, 0.881161), (0, 14, 1.17052), (0, 16, 0.990126), (0, 17, 0.708218),
+    (0, 18, 0.931484), (1, 2, 1.02274), (1, 3, 0.97012), (1, 4, 0.945939), (1, 5, 0.946914),
+    (1, 6, 0.973384), (1, 7, 0.95934), (1, 8, 0.94552), (1, 14, 1.08632), (1, 15, 1.021),
+    (1, 16, 1.00571), (1, 17, 0.746954), (1, 18, 0.902271), (2, 3, 0.925053), (2, 4, 0.960237),
+    (2, 5, 0.906849), (2, 6, 0.897362), (2, 7, 0.726255), (2, 8, 0.859764), (2, 9, 0.855134),
+    (2, 10, 0.831229), (2, 11, 0.80831), (2, 12, 0.786323), (2, 13, 0.765171), (2, 14, 1.28179),
+    (2, 16, 1.5), (2, 17, 0.849408), (2, 18, 0.955052), (3, 4, 1.02256), (3, 6, 1.01306),
+    (3, 8, 1.00532), (3, 14, 1.16446), (3, 17, 0.693168), (3, 18, 0.946871), (4, 6, 1.0049),
+    (4, 14, 1.034787), (5, 14, 1.3), (6, 14, 1.3), (9, 18, 1.008692), (10, 18, 1.010126),
+    (11, 18, 1.011501), (12, 18, 1.012821), (13, 18, 1.014089), (14, 16, 1.1),
+];
+
+/// 二元共形参数 Uij
+#[rustfmt::skip]
+const UIJ: [(usize, usize, f64); 38] = [
+    (0, 1, 0.886106), (0, 2, 0.963827), (0, 4, 0.990877), (0, 6, 0.992291), (0, 8, 1.00367),
+    (0, 9, 1.302576), (0, 10, 1.191904), (0, 11, 1.205769), (0, 12, 1.219634), (0, 13, 1.233498),
+    (0, 14, 1.15639), (0, 18, 0.736833), (1, 2, 0.835058), (1, 3, 0.816431), (1, 4, 0.915502),
+    (1, 6, 0.993556), (1, 14, 0.408838), (1, 18, 0.993476), (2, 3, 0.96987), (2, 9, 1.066638),
+    (2, 10, 1.077634), (2, 11, 1.088178), (2, 12, 1.098291), (2, 13, 1.108021), (2, 16, 0.9),
+    (2, 18, 1.04529), (3, 4, 1.065173), (3, 5, 1.25), (3, 6, 1.25), (3, 7, 1.25), (3, 8, 1.25),
+    (3, 14, 1.61666), (3, 18, 0.971926), (9, 18, 1.028973), (10, 18, 1.033754), (11, 18, 1.038338),
+    (12, 18, 1.042735), (13, 18, 1.046966),
+];
+
+/// 二元尺寸参数 Kij
+#[rustfmt::skip]
+const KIJ: [(usize, usize, f64); 31] = [
+    (0, 1, 1.00363), (0, 2, 0.995933), (0, 4, 1.007619), (0, 6, 0.997596), (0, 8, 1.002529),
+    (0, 9, 0.982962), (0, 10, 0.983565), (0, 11, 0.982707), (0, 12, 0.981849), (0, 13, 0.980991),
+    (0, 14, 1.02326), (0, 18, 1.00008), (1, 2, 0.982361), (1, 3, 1.00796), (1, 14, 1.03227),
+    (1, 18, 0.942596), (2, 3, 1.00851), (2, 9, 0.910183), (2, 10, 0.895362), (2, 11, 0.881152),
+    (2, 12, 0.86752), (2, 13, 0.854406), (2, 18, 1.00779), (3, 4, 0.986893), (3, 14, 1.02034),
+    (3, 18, 0.999969), (9, 18, 0.96813), (10, 18, 0.96287), (11, 18, 0.957828), (12, 18, 0.952441),
+    (13, 18, 0.948338),
+];
+
+/// 二元定向参数 G*ij
+#[rustfmt::skip]
+const GIJ: [(usize, usize, f64); 5] = [
+    (0, 2, 0.807653), (0, 14, 1.95731), (1, 2, 0.982746), (2, 3, 0.370296), (2, 17, 1.67309),
+];
+
+// 二元参数查表（表中 i < j）
+fn binary(table: &[(usize, usize, f64)], i: usize, j: usize) -> f64 {
+    let (i, j) = if i < j { (i, j) } else { (j, i) };
+    table
+        .iter()
+        .find(|&&(a, b, _)| a == i && b == j)
+        .map_or(1.0, |&(_, _, v)| v)
+}
+
+// 组分在 AGA8 组分表中的位置，不支持的组分（C7+）返回 None
+fn index(id: ComponentId) -> Option<usize> {
+    use ComponentId::*;
+    Some(match id {
+        C1 => 0,
+        N2 => 1,
+        Co2 => 2,
+        C2 => 3,
+        C3 => 4,
+        IC4 => 5,
+        NC4 => 6,
+        IC5 => 7,
+        NC5 => 8,
+        C6 => 9,
+        C7 => 10,
+        C8 => 11,
+        C9 => 12,
+        C10 => 13,
+        H2 => 14,
+        O2 => 15,
+        Co => 16,
+        H2o => 17,
+        H2s => 18,
+        He => 19,
+        Ar => 20,
+        C7Plus => return None,
+    })
+}
+
+/// AGA8-92DC 详细特征法（已完成混合规则计算的气体）
+pub struct Detail {
+    mw: f64,
+    k3: f64,        // K³
+    bs: [f64; 18],  // 第二维里系数各项（不含温度）
+    csn: [f64; 58], // C*n（不含温度）
+}
+
+impl Detail {
+    /// 由 AGA8 组分顺序的摩尔分数建立
+    pub fn new(x: &[f64; NC]) -> Self {
+        let mw = x.iter().zip(MW).map(|(xi, m)| xi * m).sum();
+
+        // 混合规则：K、U、G、Q、F
+        let mut k5 = 0.0;
+        let mut u5 = 0.0;
+        let mut g = 0.0;
+        let mut q = 0.0;
+        let mut f = 0.0;
+        for i in 0..NC {
+            k5 += x[i] * KI[i].powf(2.5);
+            u5 += x[i] * EI[i].powf(2.5);
+            g += x[i] * GI[i];
+            q += x[i] * QI[i];
+            f += x[i].powi(2) * FI[i];
+        }
+        k5 *= k5;
+        u5 *= u5;
+        for i in 0..NC {
+            for j in (i + 1)..NC {
+                if x[i] == 0.0 || x[j] == 0.0 {
+                    continue;
+                }
+                let xij = 2.0 * x[i] * x[j];
+                k5 += xij * (binary(&KIJ, i, j).powi(5) - 1.0) * (KI[i] * KI[j]).powf(2.5);
+                u5 += xij * (binary(&UIJ, i, j).powi(5) - 1.0) * (EI[i] * EI[j]).powf(2.5);
+                g += xij * (binary(&GIJ, i, j) - 1.0) * (GI[i] + GI[j]) / 2.0;
+            }
+        }
+        let k3 = k5.powf(0.6);
+        let u = u5.powf(0.2);
+
+        // 第二维里系数
+        let mut bs = [0.0; 18];
+        for i in 0..NC {
+            for j in i..NC {
+                if x[i] == 0.0 || x[j] == 0.0 {
+                    continue;
+                }
+                let xij = if i == j {
+                    x[i] * x[j]
+                } else {
+                    2.0 * x[i] * x[j]
+                };
+                let eij = binary(&EIJ, i, j) * (EI[i] * EI[j]).sqrt();
+                let gij = binary(&GIJ, i, j) * (GI[i] + GI[j]) / 2.0;
+                for (n, &(an, _, _, _, un, gn, qn, fnn, sn, wn)) in TERMS[..18].iter().enumerate() {
+                    let mut bnij = 1.0;
+                    if gn == 1 {
+                        bnij *= gij;
+                    }
+                    if qn == 1 {
+                        bnij *= QI[i] * QI[j];
+                    }
+                    if fnn == 1 {
+                        bnij *= (FI[i] * FI[j]).sqrt();
+                    }
+                    if sn == 1 {
+                        bnij *= SI[i] * SI[j];
+                    }
+                    if wn == 1 {
+                        bnij *= WI[i] * WI[j];
+                    }
+                    bs[n] += xij * an * eij.powf(un) * (KI[i] * KI[j]).powf(1.5) * bnij;
+                }
+            }
+        }
+
+        // 密度展开项系数
+        let mut csn = [0.0; 58];
+        for (n, &(an, _, _, _, un, gn, qn, fnn, _, _)) in TERMS.iter().enumerate().skip(12) {
+            let mut c = an * u.powf(un);
+            if gn == 1 {
+                c *= g;
+            }
+            if qn == 1 {
+                c *= q * q;
+            }
+            if fnn == 1 {
+                c *= f;
+            }
+            csn[n] = c;
+        }
+
+        Detail { mw, k3, bs, csn }
+    }
+
+    /// 由组分分析建立，含 C7+ 时返回 None
+    pub fn from_mixture(mix: &Mixture) -> Option<Self> {
+        let mut x = [0.0; NC];
+        for (c, y) in mix.components.iter().zip(&mix.y) {
+            x[index(c.id)?] += y;
+        }
+        Some(Self::new(&x))
+    }
+
+    /// 偏差因子，d 为摩尔密度 mol/dm³
+    fn z_at(&self, t: f64, d: f64) -> f64 {
+        let b: f64 = self
+            .bs
+            .iter()
+            .zip(&TERMS[..18])
+            .map(|(bs, term)| bs * t.powf(-term.4))
+            .sum();
+        let dr = self.k3 * d;
+
+        let mut sum = 0.0;
+        let mut c13_18 = 0.0;
+        for (n, &(_, bn, cn, kn, un, ..)) in TERMS.iter().enumerate().skip(12) {
+            let c = self.csn[n] * t.powf(-un);
+            if n < 18 {
+                c13_18 += c;
+            }
+            let drk = dr.powi(kn);
+            sum += c * (bn as f64 - cn * kn as f64 * drk) * dr.powi(bn) * (-cn * drk).exp();
+        }
+        1.0 + b * d - dr * c13_18 + sum
+    }
+
+    /// 摩尔密度，mol/dm³（kmol/m³）
+    /// t 温度 K，p 压力 MPa
+    pub fn molar_density(&self, t: f64, p: f64) -> Solution {
+        let p = p * 1000.0; // kPa
+        Newton::new()
+            .tol(p * 1e-10)
+            .solve(|d| d * R * t * self.z_at(t, d) - p, p / (R * t))
+    }
+
+    pub fn z(&self, t: f64, p: f64) -> Solution {
+        self.molar_density(t, p).map(|d| self.z_at(t, d))
+    }
+
+    pub fn mw(&self) -> f64 {
+        self.mw
+    }
+}
+
+// ============ 物性值法（SGERG-88） ============
+const MW_N2: f64 = 28.0135;
+const MW_CO2: f64 = 44.01;
+const MW_AIR: f64 = 28.9626;
+/// 标准参比条件（0 ℃，101.325 kPa）下干空气密度，kg/m³
+const RHO_AIR: f64 = 1.292923;
+const T0: f64 = 273.15;
+const P0: f64 = 0.101325;
+/// 通用气体常数，MPa·m³/(kmol·K)
+const R_KMOL: f64 = 0.00831451;
+
+/// SGERG-88 物性值法：以相对密度、N2、CO2 表征的气体
+pub struct Gross {
+    x_ch: f64,
+    x_n2: f64,
+    x_co2: f64,
+    h: f64, // 等效烃摩尔发热量，kJ/mol
+    mw: f64,
+}
+
+impl Gross {
+    /// 参数:
+    ///   rg   – 标准参比条件下的相对密度（真实气体）
+    ///   yn2  – N2 摩尔分数
+    ///   yco2 – CO2 摩尔分数
+    pub fn new(rg: f64, yn2: f64, yco2: f64) -> Self {
+        let x_ch = 1.0 - yn2 - yco2;
+        let mut gas = Gross {
+            x_ch,
+            x_n2: yn2,
+            x_co2: yco2,
+            h: 0.0,
+            mw: 0.0,
+        };
+
+        // 以理想气体分子量给出初值，再用割线法使计算相对密度等于 rg
+        let mw_ch = (rg * MW_AIR - yn2 * MW_N2 - yco2 * MW_CO2) / x_ch;
+        let mut h0 = (mw_ch + 2.709328) / 0.021062199;
+        let mut h1 = h0 * 1.001;
+        let mut f0 = gas.with_h(h0).rg() - rg;
+        for _ in 0..50 {
+            let f1 = gas.with_h(h1).rg() - rg;
+            if f1.abs() < 1e-12 || f1 == f0 {
+                break;
+            }
+            let h2 = h1 - f1 * (h1 - h0) / (f1 - f0);
+            h0 = h1;
+            f0 = f1;
+            h1 = h2;
+        }
+        gas.with_h(h1);
+        gas
+    }
+
+    fn with_h(&mut self, h: f64) -> &Self {
+        self.h = h;
+        let mw_ch = -2.709328 + 0.021062199 * h;
+        self.mw = self.x_ch * mw_ch + self.x_n2 * MW_N2 + self.x_co2 * MW_CO2;
+        self
+    }
+
+    // 标准参比条件下的真实相对密度
+    fn rg(&self) -> f64 {
+        let z0 = 1.0 + self.b(T0) * P0 / (R_KMOL * T0);
+        self.mw * P0 / (z0 * R_KMOL * T0) / RHO_AIR
+    }
+
+    // 第二维里系数，m³/kmol
+    fn b(&self, t: f64) -> f64 {
+        let h = self.h;
+        let b_h = (-0.425468 + 8.77118e-4 * h - 8.24747e-7 * h * h)
+            + (2.865e-3 - 5.56281e-6 * h + 4.31436e-9 * h * h) * t
+            + (-4.62073e-6 + 8.8151e-9 * h - 6.08319e-12 * h * h) * t * t;
+        let b_n2 = -0.1446 + 7.4091e-4 * t - 9.1195e-7 * t * t;
+        let b_co2 = -0.868834 + 4.0376e-3 * t - 5.1657e-6 * t * t;
+        let b_n2_co2 = -0.339693 + 1.61176e-3 * t - 2.04429e-6 * t * t;
+        let b_h_n2 = (0.72 + 1.875e-5 * (320.0 - t).powi(2)) * (b_h + b_n2) / 2.0;
+        let b_h_co2 = -0.865 * (b_h * b_co2).sqrt();
+
+        let (x1, x2, x3) = (self.x_ch, self.x_n2, self.x_co2);
+        x1 * x1 * b_h
+            + 2.0 * x1 * x2 * b_h_n2
+            + 2.0 * x1 * x3 * b_h_co2
+            + x2 * x2 * b_n2
+            + 2.0 * x2 * x3 * b_n2_co2
+            + x3 * x3 * b_co2
+    }
+
+    // 第三维里系数，m⁶/kmol²
+    fn c(&self, t: f64) -> f64 {
+        let h = self.h;
+        let c_h = (-0.302488 + 6.46422e-4 * h - 3.32805e-7 * h * h)
+            + (1.95861e-3 - 4.22876e-6 * h + 2.2316e-9 * h * h) * t
+            + (-3.16302e-6 + 6.88157e-9 * h - 3.67713e-12 * h * h) * t * t;
+        let c_n2 = 7.8498e-3 - 3.9895e-5 * t + 6.1187e-8 * t * t;
+        let c_co2 = 2.0513e-3 + 3.4888e-5 * t - 8.3703e-8 * t * t;
+        let c_n2_n2_co2 = 5.52066e-3 - 1.68609e-5 * t + 1.57169e-8 * t * t;
+        let c_n2_co2_co2 = 3.58783e-3 + 8.06674e-6 * t - 3.25798e-8 * t * t;
+        let y = 0.92 + 0.0013 * (t - 270.0);
+        let c_h_h_n2 = y * (c_h * c_h * c_n2).cbrt();
+        let c_h_n2_n2 = y * (c_h * c_n2 * c_n2).cbrt();
+        let c_h_h_co2 = 0.92 * (c_h * c_h * c_co2).cbrt();
+        let c_h_co2_co2 = 0.92 * (c_h * c_co2 * c_co2).cbrt();
+        let c_h_n2_co2 = 1.10 * (c_h * c_n2 * c_co2).cbrt();
+
+        let (x1, x2, x3) = (self.x_ch, self.x_n2, self.x_co2);
+        x1.powi(3) * c_h
+            + 3.0 * x1 * x1 * x2 * c_h_h_n2
+            + 3.0 * x1 * x2 * x2 * c_h_n2_n2
+            + x2.powi(3) * c_n2
+            + 3.0 * x1 * x1 * x3 * c_h_h_co2
+            + 3.0 * x1 * x3 * x3 * c_h_co2_co2
+            + x3.powi(3) * c_co2
+            + 6.0 * x1 * x2 * x3 * c_h_n2_co2
+            + 3.0 * x2 * x2 * x3 * c_n2_n2_co2
+            + 3.0 * x2 * x3 * x3 * c_n2_co2_co2
+    }
+
+    /// 摩尔密度，kmol/m³
+    /// t 温度 K，p 压力 MPa
+    pub fn molar_density(&self, t: f64, p: f64) -> Solution {
+        let b = self.b(t);
+        let c = self.c(t);
+        let rt = R_KMOL * t;
+        Newton::new().tol(p * 1e-12).solve_with_derivative(
+            |d| {
+                (
+                    d * rt * (1.0 + b * d + c * d * d) - p,
+                    rt * (1.0 + 2.0 * b * d + 3.0 * c * d * d),
+                )
+            },
+            p / rt,
+        )
+    }
+
+    pub fn z(&self, t: f64, p: f64) -> Solution {
+        let (b, c) = (self.b(t), self.c(t));
+        self.molar_density(t, p).map(|d| 1.0 + b * d + c * d * d)
+    }
+
+    pub fn mw(&self) -> f64 {
+        self.mw
+    }
+}
+
+/// AGA8 计算对象
+pub enum Aga8 {
+    Detail(Box<Detail>),
+    Gross(Gross),
+}
+
+impl Aga8 {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Aga8::Detail(_) => "AGA8-92DC",
+            Aga8::Gross(_) => "SGERG-88",
+        }
+    }
+
+    /// 偏差因子，t 温度 K，p 压力 MPa
+    pub fn z(&self, t: f64, p: f64) -> f64 {
+        self.z_solve(t, p).value
+    }
+
+    /// 偏差因子及密度迭代的诊断信息
+    pub fn z_solve(&self, t: f64, p: f64) -> Solution {
+        match self {
+            Aga8::Detail(gas) => gas.z(t, p),
+            Aga8::Gross(gas) => gas.z(t, p),
+        }
+    }
+
+    /// 密度，kg/m³
+    pub fn density(&self, t: f64, p: f64) -> f64 {
+        match self {
+            Aga8::Detail(gas) => gas.molar_density(t, p).value * gas.mw(),
+            Aga8::Gross(gas) => gas.molar_density(t, p).value * gas.mw(),
+        }
+    }
+
+    /// 等温压缩系数 cg = 1/p - (1/z)(dz/dp)，1/MPa
+    pub fn cg(&self, t: f64, p: f64) -> f64 {
+        let dp = (p * 1e-4).max(1e-6);
+        let dzdp = (self.z(t, p + dp) - self.z(t, p - dp)) / (2.0 * dp);
+        1.0 / p - dzdp / self.z(t, p)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // NIST AGA8 参考程序（Detail.cpp）示例气体，组分顺序同 MW
+    const NIST_GAS: [f64; NC] = [
+        0.77824, 0.02, 0.06, 0.08, 0.03, 0.0015, 0.003, 0.0005, 0.00165, 0.00215, 0.00088, 0.00024,
+        0.00015, 0.00009, 0.004, 0.005, 0.002, 0.0001, 0.0025, 0.007, 0.001,
+    ];
+
+    #[test]
+    fn detail_molar_mass_matches_nist_example() {
+        let gas = Detail::new(&NIST_GAS);
+        assert!((gas.mw() - 20.54333051).abs() < 1e-8, "mw = {}", gas.mw());
+    }
+
+    #[test]
+    fn detail_matches_nist_example() {
+        // 400 K、50 MPa：Z = 1.173801364147326，摩尔密度 12.80792403648801 mol/L
+        let gas = Detail::new(&NIST_GAS);
+        let z = gas.z(400.0, 50.0).value;
+        assert!((z / 1.173801364147326 - 1.0).abs() < 1e-9, "z = {z}");
+        let d = gas.molar_density(400.0, 50.0).value;
+        assert!((d / 12.80792403648801 - 1.0).abs() < 1e-9, "d = {d}");
+    }
+
+    #[test]
+    fn detail_matches_iso_12213_example_gases() {
+        // ISO 12213-2 示例气 1、2、3、5（前 10 个组分），270 K、6 MPa
+        let gases: [([f64; 10], f64); 4] = [
+            (
+                [
+                    0.965, 0.003, 0.006, 0.018, 0.0045, 0.001, 0.001, 0.0005, 0.0003, 0.0007,
+                ],
+                0.84053,
+            ),
+            (
+                [
+                    0.907, 0.031, 0.005, 0.045, 0.0084, 0.001, 0.0015, 0.0003, 0.0004, 0.0004,
+                ],
+                0.83348,
+            ),
+            (
+                [
+                    0.859, 0.01, 0.015, 0.085, 0.023, 0.0035, 0.0035, 0.0005, 0.0005, 0.0,
+                ],
+                0.79380,
+            ),
+            (
+                [
+                    0.812, 0.057, 0.076, 0.043, 0.009, 0.0015, 0.0015, 0.0, 0.0, 0.0,
+                ],
+                0.82609,
+            ),
+        ];
+        for (y, expected) in gases {
+            let mut x = [0.0; NC];
+            x[..10].copy_from_slice(&y);
+            let z = Detail::new(&x).z(270.0, 6.0).value;
+            assert!(
+                (z - expected).abs() < 5e-6,
+                "z = {z}, expected = {expected}"
+            );
+        }
+    }
+
+    #[test]
+    fn detail_approaches_ideal_gas_at_low_pressure() {
+        let gas = Detail::new(&NIST_GAS);
+        let z = gas.z(300.0, 1e-4).value;
+        assert!((z - 1.0).abs() < 1e-5, "z = {z}");
+    }
+
+    #[test]
+    fn gross_reproduces_relative_density() {
+        let gas = Gross::new(0.581, 0.003, 0.006);
+        assert!((gas.rg() - 0.581).abs() < 1e-9, "rg = {}", gas.rg());
+    }
+
+    #[test]
+    fn gross_matches_iso_12213_example_gas_1() {
+        // ISO 12213 示例气 1（rg = 0.581，N₂ 0.3%，CO₂ 0.6%），容差取标准不确定度 0.1%
+        let gas = Gross::new(0.581, 0.003, 0.006);
+        for (t, p, expected) in [(270.0, 6.0, 0.84053), (270.0, 12.0, 0.72133)] {
+            let z = gas.z(t, p).value;
+            assert!(
+                (z / expected - 1.0).abs() < 1e-3,
+                "t = {t}, p = {p}, z = {z}"
+            );
+        }
+    }
+}
@@ -0,0 +1,156 @@
+use crate::pressure::CalcError;
+use serde::{Deserialize, Serialize};
+use std::f64::consts::PI;
+
+/// 天然气绝热指数缺省值
+pub const DEFAULT_K: f64 = 1.3;
+/// 油嘴流量系数缺省值
+pub const DEFAULT_CD: f64 = 0.86;
+
+// 通用气体常数，J/(mol·K)
+const R: f64 = 8.314;
+// 空气摩尔质量，kg/mol
+const M_AIR: f64 = 0.02897;
+// 地面标准状况（20 ℃、0.101325 MPa）
+const T_SC: f64 = 293.15;
+const P_SC: f64 = 0.101325;
+
+/// 油嘴流动方程
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ChokeMethod {
+    /// Thornhill-Craver 等熵喷嘴公式（默认）
+    #[default]
+    ThornhillCraver,
+    /// 流量比法：临界流量按等熵公式计算，
+    /// 亚临界流量按 q/qc = √[1 - ((y - yc)/(1 - yc))²] 折算
+    FlowRatio,
+}
+
+impl ChokeMethod {
+    pub fn name(self) -> &'static str {
+        match self {
+            ChokeMethod::ThornhillCraver => "Thornhill-Craver",
+            ChokeMethod::FlowRatio => "流量比法",
+        }
+    }
+}
+
+/// 油嘴参数
+#[derive(Debug, Clone, Copy)]
+pub struct Choke {
+    pub method: ChokeMethod,
+    pub k: f64,  // 绝热指数
+    pub cd: f64, // 流量系数
+}
+
+/// 油嘴流动计算结果
+#[derive(Debug, Clone, Copy)]
+pub struct ChokeFlow {
+    pub qg: f64,        // 产气量，m³/d（标准状况）
+    pub d: f64,         // 油嘴直径，mm
+    pub ratio: f64,     // 压力比 p2/p1
+    pub critical: bool, // 是否为临界流
+}
+
+impl Choke {
+    /// 临界压力比 (2/(k+1))^(k/(k-1))
+    pub fn critical_ratio(&self) -> f64 {
+        (2.0 / (self.k + 1.0)).powf(self.k / (self.k - 1.0))
+    }
+
+    // 等熵喷嘴公式中的 k/(k-1)·(y^(2/k) - y^((k+1)/k))
+    fn psi(&self, y: f64) -> f64 {
+        let k = self.k;
+        k / (k - 1.0) * (y.powf(2.0 / k) - y.powf((k + 1.0) / k))
+    }
+
+    /// 直径 1 mm 的油嘴在给定上下游条件下的流量，m³/d
+    /// 等熵喷嘴公式：
+    /// qsc = Cd·A·p1·(Tsc/psc)·√[2R/(M·Z1·T1)·k/(k-1)·(y^(2/k) - y^((k+1)/k))]
+    /// 压力比低于临界压力比时为临界流，流量与下游压力无关
+    fn unit_rate(&self, rg: f64, z1: f64, t1: f64, p1: f64, p2: f64) -> (f64, f64, bool) {
+        let ratio = p2 / p1;
+        let yc = self.critical_ratio();
+        let critical = ratio <= yc;
+        let psi = match self.method {
+            ChokeMethod::ThornhillCraver => self.psi(ratio.max(yc)),
+            ChokeMethod::FlowRatio if critical => self.psi(yc),
+            ChokeMethod::FlowRatio => {
+                let x = (ratio - yc) / (1.0 - yc);
+                self.psi(yc) * (1.0 - x * x)
+            }
+        };
+        let area = PI * 1e-6 / 4.0; // d = 1 mm
+        let q = self.cd * area * p1 * T_SC / P_SC
+            * (2.0 * R / (M_AIR * rg * z1 * t1) * psi).sqrt()
+            * 86400.0;
+        (q, ratio, critical)
+    }
+
+    fn check(&self, p1: f64, p2: f64) -> Result<(), CalcError> {
+        if self.k <= 1.0 || self.cd <= 0.0 {
+            return Err(CalcError::InvalidInput(
+                "绝热指数须大于 1、流量系数须大于 0",
+            ));
+        }
+        if p2 < 0.0 || p2 >= p1 {
+            return Err(CalcError::InvalidInput("下游压力须小于上游压力"));
+        }
+        Ok(())
+    }
+
+    /// 已知油嘴直径求产气量
+    /// 参数:
+    ///   rg – 气体相对密度
+    ///   z1 – 上游压力、温度下的偏差因子
+    ///   t1 – 上游温度，K
+    ///   p1 – 上游压力，MPa
+    ///   p2 – 下游压力，MPa
+    ///   d  – 油嘴直径，mm
+    pub fn rate(
+        &self,
+        rg: f64,
+        z1: f64,
+        t1: f64,
+        p1: f64,
+        p2: f64,
+        d: f64,
+    ) -> Result<ChokeFlow, CalcError> {
+        self.check(p1, p2)?;
+        if d <= 0.0 {
+            return Err(CalcError::InvalidInput("油嘴直径须大于 0"));
+        }
+        let (q1, ratio, critical) = self.unit_rate(rg, z1, t1, p1, p2);
+        Ok(ChokeFlow {
+            qg: q1 * d * d,
+            d,
+            ratio,
+            critical,
+        })
+    }
+
+    /// 已知目标产气量（m³/d）求所需油嘴直径，参数同 `rate`
+    pub fn size(
+        &self,
+        rg: f64,
+        z1: f64,
+        t1: f64,
+        p1: f64,
+        p2: f64,
+        qg: f64,
+    ) -> Result<ChokeFlow, CalcError> {
+        self.check(p1, p2)?;
+        if qg <= 0.0 {
+            return Err(CalcError::InvalidInput("产气量须大于 0"));
+        }
+        // 流量与直径平方成正比
+        let (q1, ratio, critical) = self.unit_rate(rg, z1, t1, p1, p2);
+        Ok(ChokeFlow {
+            qg,
+            d: (qg / q1).sqrt(),
+            ratio,
+            critical,
+        })
+    }
+}
@@ -0,0 +1,205 @@
+use serde::{Deserialize, Serialize};
+
+/// 空气分子量
+pub const MW_AIR: f64 = 28.97;
+
+/// 组分代号
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ComponentId {
+    C1,
+    C2,
+    C3,
+    #[serde(rename = "iC4")]
+    IC4,
+    #[serde(rename = "nC4")]
+    NC4,
+    #[serde(rename = "iC5")]
+    IC5,
+    #[serde(rename = "nC5")]
+    NC5,
+    C6,
+    C7,
+    C8,
+    C9,
+    C10,
+    #[serde(rename = "C7+")]
+    C7Plus,
+    N2,
+    #[serde(rename = "CO2")]
+    Co2,
+    #[serde(rename = "H2S")]
+    H2s,
+    He,
+    H2,
+    #[serde(rename = "H2O")]
+    H2o,
+    O2,
+    #[serde(rename = "CO")]
+    Co,
+    Ar,
+}
+
+/// 组分物性
+/// mw 分子量  tc 临界温度 K  pc 临界压力 MPa  omega 偏心因子
+#[derive(Debug, Clone, Copy)]
+pub struct Component {
+    pub id: ComponentId,
+    pub mw: f64,
+    pub tc: f64,
+    pub pc: f64,
+    pub omega: f64,
+}
+
+const fn comp(id: ComponentId, mw: f64, tc: f64, pc: f64, omega: f64) -> Component {
+    Component {
+        id,
+        mw,
+        tc,
+        pc,
+        omega,
+    }
+}
+
+/// 内置组分库（C7+ 需由分子量和相对密度表征，不在库中）
+pub const LIBRARY: [Component; 21] = [
+    comp(ComponentId::C1, 16.043, 190.56, 4.599, 0.0115),
+    comp(ComponentId::C2, 30.070, 305.32, 4.872, 0.0995),
+    comp(ComponentId::C3, 44.097, 369.83, 4.248, 0.1523),
+    comp(ComponentId::IC4, 58.123, 407.80, 3.640, 0.1835),
+    comp(ComponentId::NC4, 58.123, 425.12, 3.796, 0.2002),
+    comp(ComponentId::IC5, 72.150, 460.40, 3.380, 0.2275),
+    comp(ComponentId::NC5, 72.150, 469.70, 3.370, 0.2515),
+    comp(ComponentId::C6, 86.177, 507.60, 3.025, 0.3013),
+    comp(ComponentId::C7, 100.204, 540.20, 2.740, 0.3495),
+    comp(ComponentId::C8, 114.231, 568.70, 2.490, 0.3996),
+    comp(ComponentId::C9, 128.258, 594.60, 2.290, 0.4435),
+    comp(ComponentId::C10, 142.285, 617.70, 2.110, 0.4923),
+    comp(ComponentId::N2, 28.014, 126.20, 3.398, 0.0377),
+    comp(ComponentId::Co2, 44.010, 304.12, 7.374, 0.2236),
+    comp(ComponentId::H2s, 34.082, 373.20, 8.940, 0.0942),
+    comp(ComponentId::He, 4.003, 5.19, 0.227, -0.3900),
+    comp(ComponentId::H2, 2.016, 33.19, 1.313, -0.2160),
+    comp(ComponentId::H2o, 18.015, 647.14, 22.064, 0.3449),
+    comp(ComponentId::O2, 31.999, 154.58, 5.043, 0.0222),
+    comp(ComponentId::Co, 28.010, 132.85, 3.494, 0.0450),
+    comp(ComponentId::Ar, 39.948, 150.86, 4.898, -0.0020),
+];
+
+impl ComponentId {
+    /// 查组分库，C7+ 返回 None
+    pub fn props(self) -> Option<Component> {
+        LIBRARY.iter().find(|c| c.id == self).copied()
+    }
+}
+
+/// C7+ 表征结果
+#[derive(Serialize, Debug, Clone, Copy)]
+pub struct PlusFraction {
+    pub mw: f64,
+    pub sg: f64,
+    pub tb: f64,    // 正常沸点，K
+    pub tc: f64,    // 临界温度，K
+    pub pc: f64,    // 临界压力，MPa
+    pub omega: f64, // 偏心因子
+}
+
+/// C7+ 表征：Riazi-Daubert 求 Tb/Tc/Pc，Edmister 求偏心因子
+/// 参数:
+///   mw – C7+ 分子量
+///   sg – C7+ 相对密度（水=1）
+pub fn characterize_plus(mw: f64, sg: f64) -> PlusFraction {
+    // Riazi-Daubert（°R, psia）
+    let tb = 6.77857
+        * mw.powf(0.401673)
+        * sg.powf(-1.58262)
+        * (3.77409e-3 * mw + 2.984036 * sg - 4.25288e-3 * mw * sg).exp();
+    let tc = 544.4 * mw.powf(0.2998) * sg.powf(1.0555) * (-1.3478e-4 * mw - 0.61641 * sg).exp();
+    let pc = 4.5203e4 * mw.powf(-0.8063) * sg.powf(1.6015) * (-1.8078e-3 * mw - 0.3084 * sg).exp();
+    // Edmister
+    let omega = 3.0 / 7.0 * (pc / 14.696).log10() / (tc / tb - 1.0) - 1.0;
+
+    PlusFraction {
+        mw,
+        sg,
+        tb: tb / 1.8,
+        tc: tc / 1.8,
+        pc: pc * 0.00689476,
+        omega,
+    }
+}
+
+/// 气体混合物（已归一化的摩尔组成）
+#[derive(Debug, Clone)]
+pub struct Mixture {
+    pub components: Vec<Component>,
+    pub y: Vec<f64>,
+}
+
+impl Mixture {
+    /// 由组分分析建立混合物，摩尔分数按总和归一化
+    /// 含 C7+ 时必须提供 plus（C7+ 表征结果），组成总和须大于 0
+    pub fn new(analysis: &[(ComponentId, f64)], plus: Option<PlusFraction>) -> Option<Self> {
+        let total: f64 = analysis.iter().map(|(_, y)| y).sum();
+        if total <= 0.0 || analysis.iter().any(|(_, y)| *y < 0.0) {
+            return None;
+        }
+
+        let mut components = Vec::with_capacity(analysis.len());
+        let mut y = Vec::with_capacity(analysis.len());
+        for &(id, yi) in analysis {
+            let c = match id.props() {
+                Some(c) => c,
+                None => {
+                    let p = plus?;
+                    comp(id, p.mw, p.tc, p.pc, p.omega)
+                }
+            };
+            components.push(c);
+            y.push(yi / total);
+        }
+        Some(Mixture { components, y })
+    }
+
+    /// 某组分的摩尔分数
+    pub fn fraction(&self, id: ComponentId) -> f64 {
+        self.components
+            .iter()
+            .zip(&self.y)
+            .filter(|(c, _)| c.id == id)
+            .map(|(_, y)| y)
+            .sum()
+    }
+
+    /// 视分子量
+    pub fn mw(&self) -> f64 {
+        self.components
+            .iter()
+            .zip(&self.y)
+            .map(|(c, y)| c.mw * y)
+            .sum()
+    }
+
+    /// 气体相对密度（空气=1）
+    pub fn rg(&self) -> f64 {
+        self.mw() / MW_AIR
+    }
+
+    /// Kay 规则拟临界参数
+    /// 返回:
+    ///   (拟临界压力 MPa, 拟临界温度 K)
+    pub fn kay(&self) -> (f64, f64) {
+        let pc = self
+            .components
+            .iter()
+            .zip(&self.y)
+            .map(|(c, y)| c.pc * y)
+            .sum();
+        let tc = self
+            .components
+            .iter()
+            .zip(&self.y)
+            .map(|(c, y)| c.tc * y)
+            .sum();
+        (pc, tc)
+    }
+}
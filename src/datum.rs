@@ -0,0 +1,111 @@
+use crate::pressure::{self, CalcError};
+use crate::solver::Solution;
+use crate::zfactor::ZMethod;
+
+// 重力加速度，m/s²
+const G: f64 = 9.81;
+
+/// 压力折算所用的气体参数
+#[derive(Debug, Clone, Copy)]
+pub struct GasColumn {
+    pub method: ZMethod,
+    pub rg: f64,
+    pub pc: f64, // MPa
+    pub tc: f64, // K
+}
+
+/// 气液界面（气水或气油界面）
+#[derive(Debug, Clone, Copy)]
+pub struct Contact {
+    pub elevation: f64, // 界面海拔（海平面以下为负），m
+    pub density: f64,   // 界面以下液相密度，kg/m³
+}
+
+/// 折算结果
+#[derive(Debug, Clone, Copy)]
+pub struct DatumPressure {
+    pub p: f64,         // 基准面压力，MPa
+    pub t: f64,         // 基准面温度，K
+    pub gas_dh: f64,    // 按气柱折算的垂直高差（向下为正），m
+    pub liquid_dh: f64, // 按液柱折算的垂直高差，m
+    pub sol: Solution,  // 气柱积分的迭代诊断信息
+}
+
+/// 将压力计实测压力折算到基准面
+/// 界面以上按静气柱（平均温度、平均偏差系数法）折算，界面以下按液柱梯度折算
+/// 参数:
+///   gas     – 气体参数
+///   p       – 压力计实测压力，MPa
+///   elev    – 压力计海拔（海平面以下为负），m
+///   t       – 压力计处温度，K
+///   t_grad  – 地温梯度（向下为正），K/m
+///   datum   – 基准面海拔，m
+///   contact – 气液界面，无则全程按气柱折算
+pub fn to_datum(
+    gas: &GasColumn,
+    p: f64,
+    elev: f64,
+    t: f64,
+    t_grad: f64,
+    datum: f64,
+    contact: Option<Contact>,
+) -> Result<DatumPressure, CalcError> {
+    pressure::check_pressure(p)?;
+    let temperature = |e: f64| t + t_grad * (elev - e);
+    let t_datum = temperature(datum);
+    if t_datum <= 0.0 {
+        return Err(CalcError::InvalidInput("基准面温度须大于 0"));
+    }
+
+    // 以界面为分界点将压力计至基准面的路径分为两段
+    let mut stops = vec![elev];
+    if let Some(c) = contact {
+        if (c.elevation - elev) * (datum - c.elevation) > 0.0 {
+            stops.push(c.elevation);
+        }
+    }
+    stops.push(datum);
+
+    let mut result = DatumPressure {
+        p,
+        t: t_datum,
+        gas_dh: 0.0,
+        liquid_dh: 0.0,
+        sol: Solution::exact(p),
+    };
+    for w in stops.windows(2) {
+        let (from, to) = (w[0], w[1]);
+        // 垂直高差，向下为正
+        let dh = from - to;
+        let below = contact.filter(|c| 0.5 * (from + to) < c.elevation);
+        match below {
+            Some(c) => {
+                result.p += c.density * G * dh * 1e-6;
+                result.liquid_dh += dh;
+            }
+            None => {
+                let sol = pressure::pws(
+                    gas.method,
+                    gas.rg,
+                    gas.pc,
+                    gas.tc,
+                    dh,
+                    temperature(from),
+                    temperature(to),
+                    result.p,
+                );
+                result.p = sol.value;
+                result.gas_dh += dh;
+                result.sol = Solution {
+                    iterations: result.sol.iterations + sol.iterations,
+                    converged: result.sol.converged && sol.converged,
+                    ..sol
+                };
+            }
+        }
+        pressure::check_pressure(result.p)?;
+    }
+    result.sol.value = result.p;
+    pressure::check_converged(result.sol)?;
+    Ok(result)
+}
@@ -0,0 +1,296 @@
+use crate::pressure::{self, CalcError};
+use crate::pseudo_pressure::{self, Gas};
+use crate::regression::{self, LinearFit};
+use serde::{Deserialize, Serialize};
+
+// 无阻流量对应的井底流压（地面标准大气压），MPa
+const P_SC: f64 = 0.101325;
+// IPR 曲线的输出点数
+const IPR_POINTS: usize = 21;
+
+/// 产能试井类型
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DeliverabilityTest {
+    /// 回压试井（系统试井，各产量均达到稳定，默认）
+    #[default]
+    BackPressure,
+    /// 等时试井（每次开井前关井恢复到地层压力，另有一个延时稳定点）
+    Isochronal,
+    /// 修正等时试井（等时关井，以各次开井前的关井压力计算压差）
+    ModifiedIsochronal,
+}
+
+impl DeliverabilityTest {
+    pub fn name(self) -> &'static str {
+        match self {
+            DeliverabilityTest::BackPressure => "回压试井",
+            DeliverabilityTest::Isochronal => "等时试井",
+            DeliverabilityTest::ModifiedIsochronal => "修正等时试井",
+        }
+    }
+}
+
+/// 产能方程的压力函数形式
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PotentialForm {
+    /// 压力平方 Δ(p²)，MPa²（默认）
+    #[default]
+    PressureSquared,
+    /// 拟压力 Δm(p)，MPa²/(mPa·s)
+    PseudoPressure,
+}
+
+impl PotentialForm {
+    pub fn name(self) -> &'static str {
+        match self {
+            PotentialForm::PressureSquared => "压力平方",
+            PotentialForm::PseudoPressure => "拟压力",
+        }
+    }
+}
+
+/// 产能试井的一个测试点
+#[derive(Deserialize, Debug, Clone, Copy)]
+pub struct TestPoint {
+    pub q: f64,           // 产气量，10⁴m³/d
+    pub pwf: f64,         // 流动结束时的井底流压，MPa
+    pub pws: Option<f64>, // 开井前的关井压力，MPa（修正等时试井必填，缺省为地层压力）
+    #[serde(default)]
+    pub extended: bool, // 延时稳定点（等时、修正等时试井）
+}
+
+/// 测试点的整理结果
+#[derive(Serialize, Debug, Clone, Copy)]
+pub struct TestRow {
+    pub q: f64,
+    pub pwf: f64,
+    pub p_start: f64,   // 计算压差所用的起始压力，MPa
+    pub dp: f64,        // 压力平方差或拟压力差
+    pub dp_over_q: f64, // Δ/q，LIT 作图用
+    pub extended: bool,
+    pub q_cn: f64, // 指数式方程的拟合产量（不稳定点按不稳定系数、延时点按稳定系数计算）
+    pub q_lit: f64, // 二项式方程的拟合产量
+}
+
+/// 指数式（Rawlins-Schellhardt）产能方程 q = C·Δⁿ
+#[derive(Serialize, Debug, Clone, Copy)]
+pub struct CnCoefficients {
+    pub c: f64,           // 稳定产能系数
+    pub n: f64,           // 产能指数
+    pub n_in_range: bool, // n 是否在 0.5～1 的物理范围内（超出时测试数据可疑）
+    pub c_transient: f64, // 不稳定点回归的产能系数（回压试井与 c 相同）
+    pub fit: LinearFit,   // lg q - lg Δ 回归统计
+}
+
+/// 二项式（Houpeurt/Forchheimer LIT）产能方程 Δ = a·q + b·q²
+#[derive(Serialize, Debug, Clone, Copy)]
+pub struct LitCoefficients {
+    pub a: f64,           // 稳定层流系数
+    pub b: f64,           // 紊流系数
+    pub a_transient: f64, // 不稳定点回归的层流系数（回压试井与 a 相同）
+    pub fit: LinearFit,   // Δ/q - q 回归统计
+}
+
+impl CnCoefficients {
+    fn rate(&self, c: f64, dp: f64) -> f64 {
+        c * dp.max(0.0).powf(self.n)
+    }
+}
+
+impl LitCoefficients {
+    // q = 2Δ/[a + √(a² + 4bΔ)]，b→0 时退化为 Δ/a
+    fn rate(&self, a: f64, dp: f64) -> f64 {
+        if dp <= 0.0 {
+            return 0.0;
+        }
+        2.0 * dp / (a + (a * a + 4.0 * self.b * dp).sqrt())
+    }
+}
+
+/// IPR 曲线上的一点
+#[derive(Serialize, Debug, Clone, Copy)]
+pub struct IprPoint {
+    pub pwf: f64,
+    pub dp: f64,
+    pub q_cn: f64,  // 指数式方程产量，10⁴m³/d
+    pub q_lit: f64, // 二项式方程产量，10⁴m³/d
+}
+
+/// 产能分析结果
+#[derive(Debug, Clone)]
+pub struct Deliverability {
+    pub rows: Vec<TestRow>,
+    pub cn: CnCoefficients,
+    pub lit: LitCoefficients,
+    pub aof_cn: f64,  // 指数式方程无阻流量，10⁴m³/d
+    pub aof_lit: f64, // 二项式方程无阻流量，10⁴m³/d
+    pub ipr: Vec<IprPoint>,
+}
+
+/// 产能试井分析：回归指数式与二项式产能方程，计算无阻流量（井底流压取 0.101325 MPa）和 IPR 曲线
+/// 等时、修正等时试井由不稳定点回归 n 和 b，再用延时稳定点确定稳定的 C 和 a
+/// 参数:
+///   test   – 试井类型
+///   form   – 压力平方或拟压力形式
+///   gas    – 气体物性（拟压力形式使用，温度取地层温度）
+///   pr     – 平均地层压力，MPa
+///   points – 测试点
+pub fn analyze(
+    test: DeliverabilityTest,
+    form: PotentialForm,
+    gas: &Gas,
+    pr: f64,
+    points: &[TestPoint],
+) -> Result<Deliverability, CalcError> {
+    pressure::check_pressure(pr)?;
+    let starts = points
+        .iter()
+        .map(|pt| match (test, pt.extended) {
+            (DeliverabilityTest::BackPressure, _) | (_, true) => Ok(pr),
+            (DeliverabilityTest::Isochronal, false) => Ok(pt.pws.unwrap_or(pr)),
+            (DeliverabilityTest::ModifiedIsochronal, false) => pt.pws.ok_or(
+                CalcError::InvalidInput("修正等时试井须给出各次开井前的关井压力"),
+            ),
+        })
+        .collect::<Result<Vec<f64>, _>>()?;
+
+    // 所需压力一次求出压力函数值：地层压力、大气压、测试点、IPR 曲线
+    let ipr_p: Vec<f64> = (0..IPR_POINTS)
+        .map(|k| P_SC + (pr - P_SC) * (1.0 - k as f64 / (IPR_POINTS - 1) as f64))
+        .collect();
+    let mut pressures = vec![pr, P_SC];
+    pressures.extend(points.iter().map(|pt| pt.pwf));
+    pressures.extend(&starts);
+    pressures.extend(&ipr_p);
+    let psi = match form {
+        PotentialForm::PressureSquared => pressures
+            .iter()
+            .map(|&p| Ok(pressure::check_pressure(p)?.powi(2)))
+            .collect::<Result<Vec<f64>, CalcError>>()?,
+        PotentialForm::PseudoPressure => pseudo_pressure::table(gas, &pressures, pr)?
+            .into_iter()
+            .map(|r| r.mp)
+            .collect(),
+    };
+    let n = points.len();
+    let (psi_pr, psi_sc) = (psi[0], psi[1]);
+    let (psi_wf, rest) = psi[2..].split_at(n);
+    let (psi_start, psi_ipr) = rest.split_at(n);
+
+    let dps: Vec<f64> = (0..n).map(|i| psi_start[i] - psi_wf[i]).collect();
+    if points
+        .iter()
+        .zip(&dps)
+        .any(|(pt, &dp)| pt.q <= 0.0 || dp <= 0.0)
+    {
+        return Err(CalcError::InvalidInput(
+            "测试点产量须为正且井底流压须低于起始压力",
+        ));
+    }
+
+    // 回压试井全部点参与回归；等时、修正等时试井以不稳定点回归，取最后一个延时点为稳定点
+    let transient: Vec<usize> = match test {
+        DeliverabilityTest::BackPressure => (0..n).collect(),
+        _ => (0..n).filter(|&i| !points[i].extended).collect(),
+    };
+    let stabilized = match test {
+        DeliverabilityTest::BackPressure => None,
+        _ => Some(
+            (0..n)
+                .rev()
+                .find(|&i| points[i].extended)
+                .ok_or(CalcError::InvalidInput("等时试井须有一个延时稳定点"))?,
+        ),
+    };
+
+    let (x, y): (Vec<f64>, Vec<f64>) = transient
+        .iter()
+        .map(|&i| (dps[i].log10(), points[i].q.log10()))
+        .unzip();
+    let cn_fit = regression::linear_fit(&x, &y)?;
+    let c_transient = 10f64.powf(cn_fit.intercept);
+    let cn = CnCoefficients {
+        c: stabilized.map_or(c_transient, |i| points[i].q / dps[i].powf(cn_fit.slope)),
+        n: cn_fit.slope,
+        n_in_range: (0.5..=1.0).contains(&cn_fit.slope),
+        c_transient,
+        fit: cn_fit,
+    };
+    if cn.n <= 0.0 {
+        return Err(CalcError::InvalidInput("产能指数 n 须为正，测试数据不合理"));
+    }
+
+    let (x, y): (Vec<f64>, Vec<f64>) = transient
+        .iter()
+        .map(|&i| (points[i].q, dps[i] / points[i].q))
+        .unzip();
+    let lit_fit = regression::linear_fit(&x, &y)?;
+    let lit = LitCoefficients {
+        a: stabilized.map_or(lit_fit.intercept, |i| {
+            dps[i] / points[i].q - lit_fit.slope * points[i].q
+        }),
+        b: lit_fit.slope,
+        a_transient: lit_fit.intercept,
+        fit: lit_fit,
+    };
+    if lit.b < 0.0 {
+        return Err(CalcError::InvalidInput(
+            "紊流系数 b 不能为负，测试数据不合理",
+        ));
+    }
+
+    let dp_aof = psi_pr - psi_sc;
+    let aof_cn = cn.rate(cn.c, dp_aof);
+    let aof_lit = lit.rate(lit.a, dp_aof);
+    if !(aof_lit.is_finite() && aof_lit > 0.0) {
+        return Err(CalcError::InvalidInput(
+            "二项式系数 a、b 不合理，无法求得无阻流量",
+        ));
+    }
+
+    let rows = (0..n)
+        .map(|i| {
+            let pt = &points[i];
+            // 延时点的压差取自地层压力，与稳定方程一致
+            let (c, a) = if stabilized.is_none() || pt.extended {
+                (cn.c, lit.a)
+            } else {
+                (cn.c_transient, lit.a_transient)
+            };
+            TestRow {
+                q: pt.q,
+                pwf: pt.pwf,
+                p_start: starts[i],
+                dp: dps[i],
+                dp_over_q: dps[i] / pt.q,
+                extended: pt.extended,
+                q_cn: cn.rate(c, dps[i]),
+                q_lit: lit.rate(a, dps[i]),
+            }
+        })
+        .collect();
+    let ipr = ipr_p
+        .iter()
+        .zip(psi_ipr)
+        .map(|(&pwf, &psi_wf)| {
+            let dp = psi_pr - psi_wf;
+            IprPoint {
+                pwf,
+                dp,
+                q_cn: cn.rate(cn.c, dp),
+                q_lit: lit.rate(lit.a, dp),
+            }
+        })
+        .collect();
+
+    Ok(Deliverability {
+        rows,
+        cn,
+        lit,
+        aof_cn,
+        aof_lit,
+        ipr,
+    })
+}
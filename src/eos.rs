@@ -0,0 +1,421 @@
+use crate::composition::{Component, ComponentId, Mixture};
+use serde::{Deserialize, Serialize};
+use std::f64::consts::{PI, SQRT_2};
+
+/// 通用气体常数，MPa·m³/(mol·K)
+const R: f64 = 8.314e-6;
+// 闪蒸逐次替代的最大迭代次数与收敛容差（Σ(Δln K)²）
+const FLASH_MAX_ITER: u32 = 500;
+const FLASH_TOL: f64 = 1e-12;
+
+/// 立方型状态方程
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum EosModel {
+    /// Peng-Robinson（默认）
+    #[default]
+    PengRobinson,
+    /// Soave-Redlich-Kwong
+    Srk,
+}
+
+impl EosModel {
+    // (Ωa, Ωb, δ1, δ2)
+    fn params(self) -> (f64, f64, f64, f64) {
+        match self {
+            EosModel::PengRobinson => (0.45724, 0.07780, 1.0 + SQRT_2, 1.0 - SQRT_2),
+            EosModel::Srk => (0.42748, 0.08664, 1.0, 0.0),
+        }
+    }
+
+    // α(T) 中的 m(ω)
+    fn m(self, omega: f64) -> f64 {
+        match self {
+            EosModel::PengRobinson if omega > 0.49 => {
+                0.379642 + 1.48503 * omega - 0.164423 * omega.powi(2) + 0.016666 * omega.powi(3)
+            }
+            EosModel::PengRobinson => 0.37464 + 1.54226 * omega - 0.26992 * omega.powi(2),
+            EosModel::Srk => 0.480 + 1.574 * omega - 0.176 * omega.powi(2),
+        }
+    }
+}
+
+/// 相态（选根用）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Phase {
+    Liquid,
+    Vapor,
+    /// 取 Gibbs 自由能最小的根
+    Stable,
+}
+
+/// 缺省二元交互作用系数
+fn default_kij(a: ComponentId, b: ComponentId) -> f64 {
+    use ComponentId::*;
+    let hc = |c: ComponentId| !matches!(c, N2 | Co2 | H2s | He | H2 | H2o | O2 | Co | Ar);
+    match (a, b) {
+        _ if a == b => 0.0,
+        (N2, Co2) | (Co2, N2) => -0.017,
+        (N2, H2s) | (H2s, N2) => 0.176,
+        (Co2, H2s) | (H2s, Co2) => 0.097,
+        (N2, C1) | (C1, N2) => 0.025,
+        (N2, C2) | (C2, N2) => 0.010,
+        (N2, c) | (c, N2) if hc(c) => 0.090,
+        (Co2, C1) | (C1, Co2) => 0.105,
+        (Co2, c) | (c, Co2) if hc(c) => 0.120,
+        (H2s, C1) | (C1, H2s) => 0.085,
+        (H2s, c) | (c, H2s) if hc(c) => 0.060,
+        (H2o, c) | (c, H2o) if hc(c) => 0.480,
+        (H2o, Co2) | (Co2, H2o) => 0.190,
+        _ => 0.0,
+    }
+}
+
+/// 立方型状态方程（混合物）
+pub struct Eos {
+    pub model: EosModel,
+    pub components: Vec<Component>,
+    pub kij: Vec<Vec<f64>>,
+}
+
+/// 闪蒸结果
+#[derive(Debug, Clone)]
+pub struct Flash {
+    pub stable: bool,        // 稳定性测试：单相稳定
+    pub vapor_fraction: f64, // 气相摩尔分数
+    pub x: Vec<f64>,         // 液相组成
+    pub y: Vec<f64>,         // 气相组成
+    pub z_liquid: Option<f64>,
+    pub z_vapor: Option<f64>,
+    pub iterations: u32, // 逐次替代迭代次数（单相稳定时为 0）
+    pub residual: f64,   // 终止时 Σ(Δln K)²
+    pub converged: bool, // 是否收敛
+}
+
+impl Eos {
+    /// 建立状态方程，kij 缺省时使用内置二元交互作用系数
+    /// 给出的 kij 须为 n×n 矩阵（n 为组分数），否则返回 None
+    pub fn new(model: EosModel, mix: &Mixture, kij: Option<Vec<Vec<f64>>>) -> Option<Self> {
+        let n = mix.components.len();
+        let kij = match kij {
+            Some(k) if k.len() != n || k.iter().any(|row| row.len() != n) => return None,
+            Some(k) => k,
+            None => mix
+                .components
+                .iter()
+                .map(|ci| {
+                    mix.components
+                        .iter()
+                        .map(|cj| default_kij(ci.id, cj.id))
+                        .collect()
+                })
+                .collect(),
+        };
+        Some(Eos {
+            model,
+            components: mix.components.clone(),
+            kij,
+        })
+    }
+
+    // 各组分 a_i、b_i
+    fn ab(&self, t: f64) -> (Vec<f64>, Vec<f64>) {
+        let (oa, ob, _, _) = self.model.params();
+        self.components
+            .iter()
+            .map(|c| {
+                let m = self.model.m(c.omega);
+                let alpha = (1.0 + m * (1.0 - (t / c.tc).sqrt())).powi(2);
+                (oa * (R * c.tc).powi(2) / c.pc * alpha, ob * R * c.tc / c.pc)
+            })
+            .unzip()
+    }
+
+    // 混合规则：返回 (A, B, Σj xj·aij 向量, a, b, bi)
+    fn mix(&self, t: f64, p: f64, x: &[f64]) -> (f64, f64, Vec<f64>, f64, f64, Vec<f64>) {
+        let (ai, bi) = self.ab(t);
+        let n = ai.len();
+        let sum_a: Vec<f64> = (0..n)
+            .map(|i| {
+                (0..n)
+                    .map(|j| x[j] * (ai[i] * ai[j]).sqrt() * (1.0 - self.kij[i][j]))
+                    .sum()
+            })
+            .collect();
+        let a: f64 = (0..n).map(|i| x[i] * sum_a[i]).sum();
+        let b: f64 = (0..n).map(|i| x[i] * bi[i]).sum();
+        let big_a = a * p / (R * t).powi(2);
+        let big_b = b * p / (R * t);
+        (big_a, big_b, sum_a, a, b, bi)
+    }
+
+    /// 计算指定相态的偏差因子 z
+    pub fn z(&self, t: f64, p: f64, x: &[f64], phase: Phase) -> f64 {
+        let (big_a, big_b, ..) = self.mix(t, p, x);
+        self.select_root(big_a, big_b, phase)
+    }
+
+    fn select_root(&self, big_a: f64, big_b: f64, phase: Phase) -> f64 {
+        let (_, _, d1, d2) = self.model.params();
+        let u = d1 + d2;
+        let w = d1 * d2;
+        let roots = cubic_roots(
+            -(1.0 + big_b - u * big_b),
+            big_a + w * big_b.powi(2) - u * big_b - u * big_b.powi(2),
+            -(big_a * big_b + w * big_b.powi(2) + w * big_b.powi(3)),
+        );
+        let roots: Vec<f64> = roots.into_iter().filter(|&z| z > big_b).collect();
+        let zmin = roots.iter().cloned().fold(f64::INFINITY, f64::min);
+        let zmax = roots.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+        match phase {
+            Phase::Liquid => zmin,
+            Phase::Vapor => zmax,
+            Phase::Stable => {
+                // 无量纲 Gibbs 自由能（相对值）
+                let g = |z: f64| {
+                    z - 1.0
+                        - (z - big_b).ln()
+                        - big_a / (big_b * (d1 - d2)) * ((z + d1 * big_b) / (z + d2 * big_b)).ln()
+                };
+                if g(zmin) < g(zmax) {
+                    zmin
+                } else {
+                    zmax
+                }
+            }
+        }
+    }
+
+    /// 逸度系数对数 ln φi
+    /// 返回:
+    ///   (z, ln φ)
+    pub fn ln_phi(&self, t: f64, p: f64, x: &[f64], phase: Phase) -> (f64, Vec<f64>) {
+        let (_, _, d1, d2) = self.model.params();
+        let (big_a, big_b, sum_a, a, b, bi) = self.mix(t, p, x);
+        let z = self.select_root(big_a, big_b, phase);
+        let ln_term = ((z + d1 * big_b) / (z + d2 * big_b)).ln();
+        let ln_phi = (0..x.len())
+            .map(|i| {
+                bi[i] / b * (z - 1.0)
+                    - (z - big_b).ln()
+                    - big_a / (big_b * (d1 - d2)) * (2.0 * sum_a[i] / a - bi[i] / b) * ln_term
+            })
+            .collect();
+        (z, ln_phi)
+    }
+
+    /// 逸度，MPa
+    pub fn fugacity(&self, t: f64, p: f64, x: &[f64], phase: Phase) -> Vec<f64> {
+        let (_, ln_phi) = self.ln_phi(t, p, x, phase);
+        x.iter()
+            .zip(ln_phi)
+            .map(|(xi, lp)| xi * p * lp.exp())
+            .collect()
+    }
+
+    /// 密度，kg/m³
+    pub fn density(&self, t: f64, p: f64, x: &[f64], z: f64) -> f64 {
+        let mw: f64 = self.components.iter().zip(x).map(|(c, xi)| c.mw * xi).sum();
+        p * mw * 1e-3 / (z * R * t)
+    }
+
+    // Wilson 方程初值 K
+    fn wilson_k(&self, t: f64, p: f64) -> Vec<f64> {
+        self.components
+            .iter()
+            .map(|c| c.pc / p * (5.373 * (1.0 + c.omega) * (1.0 - c.tc / t)).exp())
+            .collect()
+    }
+
+    /// Michelsen 稳定性测试
+    /// 返回:
+    ///   true 表示单相稳定
+    pub fn is_stable(&self, t: f64, p: f64, feed: &[f64]) -> bool {
+        // 摩尔分数为 0 的组分取下限，避免 ln(0)
+        let feed: Vec<f64> = feed.iter().map(|zi| zi.max(1e-300)).collect();
+        let (_, ln_phi_z) = self.ln_phi(t, p, &feed, Phase::Stable);
+        let d: Vec<f64> = feed
+            .iter()
+            .zip(&ln_phi_z)
+            .map(|(zi, lp)| zi.ln() + lp)
+            .collect();
+        let k = self.wilson_k(t, p);
+
+        for trial in [Phase::Vapor, Phase::Liquid] {
+            let mut w: Vec<f64> = feed
+                .iter()
+                .zip(&k)
+                .map(|(zi, ki)| {
+                    if trial == Phase::Vapor {
+                        zi * ki
+                    } else {
+                        zi / ki
+                    }
+                })
+                .collect();
+            for _ in 0..200 {
+                let sum: f64 = w.iter().sum();
+                let xw: Vec<f64> = w.iter().map(|wi| wi / sum).collect();
+                let (_, ln_phi_w) = self.ln_phi(t, p, &xw, trial);
+                let w_new: Vec<f64> = d
+                    .iter()
+                    .zip(&ln_phi_w)
+                    .map(|(di, lp)| (di - lp).exp())
+                    .collect();
+                let err: f64 = w_new
+                    .iter()
+                    .zip(&w)
+                    .map(|(a, b)| (a.ln() - b.ln()).powi(2))
+                    .sum();
+                w = w_new;
+                if err < 1e-12 {
+                    break;
+                }
+            }
+            let sum: f64 = w.iter().sum();
+            // 平凡解：试探相与原料相同
+            let trivial: f64 = w
+                .iter()
+                .zip(&feed)
+                .map(|(wi, zi)| (wi / sum / zi).ln().powi(2))
+                .sum();
+            if sum > 1.0 + 1e-8 && trivial > 1e-4 {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// 两相等温闪蒸（稳定性测试 + Rachford-Rice + 逐次替代）
+    pub fn flash(&self, t: f64, p: f64, feed: &[f64]) -> Flash {
+        if self.is_stable(t, p, feed) {
+            let z = self.z(t, p, feed, Phase::Stable);
+            // 以假临界温度判别单相为气相或液相
+            let tpc: f64 = self
+                .components
+                .iter()
+                .zip(feed)
+                .map(|(c, zi)| c.tc * zi)
+                .sum();
+            let vapor = t >= tpc;
+            return Flash {
+                stable: true,
+                vapor_fraction: if vapor { 1.0 } else { 0.0 },
+                x: feed.to_vec(),
+                y: feed.to_vec(),
+                z_liquid: (!vapor).then_some(z),
+                z_vapor: vapor.then_some(z),
+                iterations: 0,
+                residual: 0.0,
+                converged: z.is_finite(),
+            };
+        }
+
+        let mut k = self.wilson_k(t, p);
+        let mut v = 0.5;
+        let mut x = feed.to_vec();
+        let mut y = feed.to_vec();
+        let mut zl = 0.0;
+        let mut zv = 0.0;
+        let mut iterations = 0;
+        let mut residual = f64::INFINITY;
+        while iterations < FLASH_MAX_ITER {
+            iterations += 1;
+            v = rachford_rice(feed, &k);
+            x = feed
+                .iter()
+                .zip(&k)
+                .map(|(zi, ki)| zi / (1.0 + v * (ki - 1.0)))
+                .collect();
+            y = x.iter().zip(&k).map(|(xi, ki)| xi * ki).collect();
+            let (z_l, ln_phi_l) = self.ln_phi(t, p, &x, Phase::Liquid);
+            let (z_v, ln_phi_v) = self.ln_phi(t, p, &y, Phase::Vapor);
+            zl = z_l;
+            zv = z_v;
+            let k_new: Vec<f64> = ln_phi_l
+                .iter()
+                .zip(&ln_phi_v)
+                .map(|(l, g)| (l - g).exp())
+                .collect();
+            let err: f64 = k_new
+                .iter()
+                .zip(&k)
+                .map(|(a, b)| (a.ln() - b.ln()).powi(2))
+                .sum();
+            k = k_new;
+            residual = err;
+            if err < FLASH_TOL {
+                break;
+            }
+        }
+
+        Flash {
+            stable: false,
+            vapor_fraction: v,
+            x,
+            y,
+            z_liquid: Some(zl),
+            z_vapor: Some(zv),
+            iterations,
+            residual,
+            converged: residual < FLASH_TOL,
+        }
+    }
+}
+
+/// Rachford-Rice 方程求气相摩尔分数（二分法 + 牛顿法），结果限定在 [0, 1]
+fn rachford_rice(feed: &[f64], k: &[f64]) -> f64 {
+    let f = |v: f64| -> f64 {
+        feed.iter()
+            .zip(k)
+            .map(|(zi, ki)| zi * (ki - 1.0) / (1.0 + v * (ki - 1.0)))
+            .sum()
+    };
+    if f(0.0) <= 0.0 {
+        return 0.0;
+    }
+    if f(1.0) >= 0.0 {
+        return 1.0;
+    }
+    let (mut lo, mut hi) = (0.0, 1.0);
+    let mut v = 0.5;
+    for _ in 0..100 {
+        let fv = f(v);
+        if fv > 0.0 {
+            lo = v;
+        } else {
+            hi = v;
+        }
+        let df: f64 = -feed
+            .iter()
+            .zip(k)
+            .map(|(zi, ki)| zi * (ki - 1.0).powi(2) / (1.0 + v * (ki - 1.0)).powi(2))
+            .sum::<f64>();
+        let mut v_new = v - fv / df;
+        if !(lo..=hi).contains(&v_new) {
+            v_new = 0.5 * (lo + hi);
+        }
+        if (v_new - v).abs() < 1e-12 {
+            return v_new;
+        }
+        v = v_new;
+    }
+    v
+}
+
+/// 三次方程 z³ + a2·z² + a1·z + a0 = 0 的实根
+fn cubic_roots(a2: f64, a1: f64, a0: f64) -> Vec<f64> {
+    let q = (3.0 * a1 - a2.powi(2)) / 9.0;
+    let r = (9.0 * a2 * a1 - 27.0 * a0 - 2.0 * a2.powi(3)) / 54.0;
+    let d = q.powi(3) + r.powi(2);
+    if d > 0.0 {
+        let s = (r + d.sqrt()).cbrt();
+        let t = (r - d.sqrt()).cbrt();
+        vec![s + t - a2 / 3.0]
+    } else {
+        let theta = (r / (-q.powi(3)).sqrt()).clamp(-1.0, 1.0).acos();
+        (0..3)
+            .map(|k| 2.0 * (-q).sqrt() * ((theta + 2.0 * PI * k as f64) / 3.0).cos() - a2 / 3.0)
+            .collect()
+    }
+}
@@ -0,0 +1,12 @@
+/// API RP 14E 经验常数 C 的缺省值（连续生产、无固相）
+pub const DEFAULT_C: f64 = 100.0;
+
+// 英制到 SI 的换算：ft → m，lb/ft³ → kg/m³
+const FT: f64 = 0.3048;
+const LB_FT3: f64 = 16.018_463;
+
+/// 冲蚀流速（API RP 14E），m/s
+/// ve = C/√ρ（ve ft/s，ρ lb/ft³），c 为经验常数，density 为气体密度 kg/m³
+pub fn erosional_velocity(c: f64, density: f64) -> f64 {
+    FT * c / (density / LB_FT3).sqrt()
+}
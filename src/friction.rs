@@ -0,0 +1,94 @@
+use crate::solver::Newton;
+use serde::{Deserialize, Serialize};
+
+// 层流与紊流的临界雷诺数
+const RE_LAMINAR: f64 = 2000.0;
+
+/// 摩阻系数（Moody/Darcy）计算方法
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum FrictionMethod {
+    /// Jain（1976）显式公式（默认）
+    #[default]
+    Jain,
+    /// Colebrook-White 隐式方程（Moody 图），迭代求解
+    ColebrookWhite,
+    /// Chen（1979）显式公式
+    Chen,
+    /// Swamee-Jain（1976）显式公式
+    SwameeJain,
+}
+
+impl FrictionMethod {
+    pub fn name(self) -> &'static str {
+        match self {
+            FrictionMethod::Jain => "Jain",
+            FrictionMethod::ColebrookWhite => "Colebrook-White",
+            FrictionMethod::Chen => "Chen",
+            FrictionMethod::SwameeJain => "Swamee-Jain",
+        }
+    }
+
+    /// 摩阻系数（Moody/Darcy，层流时为 64/Re）
+    /// re 雷诺数，ee 绝对粗糙度 m，d 内径 m
+    pub fn factor(self, re: f64, ee: f64, d: f64) -> f64 {
+        if re <= 0.0 {
+            return 0.0;
+        }
+        if re < RE_LAMINAR {
+            return 64.0 / re;
+        }
+        let e = ee / d;
+        match self {
+            FrictionMethod::Jain => jain(re, e),
+            FrictionMethod::ColebrookWhite => colebrook_white(re, e),
+            FrictionMethod::Chen => chen(re, e),
+            FrictionMethod::SwameeJain => swamee_jain(re, e),
+        }
+    }
+}
+
+/// 雷诺数 Re = ρvd/μ
+/// density kg/m³，v m/s，d m，mu Pa·s
+pub fn reynolds(density: f64, v: f64, d: f64, mu: f64) -> f64 {
+    density * v * d / mu
+}
+
+/// 由地面产气量计算管内气体雷诺数
+/// Re = 1.776×10⁻²·qg·rg/(d·μ)，qg m³/d（20 ℃、0.101325 MPa），d m，mu mPa·s
+pub fn gas_reynolds(qg: f64, rg: f64, d: f64, mu: f64) -> f64 {
+    1.776e-2 * qg * rg / (d * mu)
+}
+
+// 1/√f = 1.14 - 2lg(e + 21.25/Re^0.9)
+fn jain(re: f64, e: f64) -> f64 {
+    (1.14 - 2.0 * (e + 21.25 / re.powf(0.9)).log10()).powi(-2)
+}
+
+// f = 0.25/[lg(e/3.7 + 5.74/Re^0.9)]²
+fn swamee_jain(re: f64, e: f64) -> f64 {
+    0.25 / (e / 3.7 + 5.74 / re.powf(0.9)).log10().powi(2)
+}
+
+// 1/√f = -2lg[e/3.7065 - 5.0452/Re·lg(e^1.1098/2.8257 + 5.8506/Re^0.8981)]
+fn chen(re: f64, e: f64) -> f64 {
+    let a = e.powf(1.1098) / 2.8257 + 5.8506 / re.powf(0.8981);
+    (-2.0 * (e / 3.7065 - 5.0452 / re * a.log10()).log10()).powi(-2)
+}
+
+// 1/√f = -2lg(e/3.7 + 2.51/(Re·√f))，以 x = 1/√f 为未知量迭代，初值取 Swamee-Jain
+fn colebrook_white(re: f64, e: f64) -> f64 {
+    let b = 2.51 / re;
+    let x0 = swamee_jain(re, e).powf(-0.5);
+    let sol = Newton::new().tol(1e-12).solve_with_derivative(
+        |x| {
+            let a = e / 3.7 + b * x;
+            (
+                x + 2.0 * a.log10(),
+                1.0 + 2.0 * b / (a * std::f64::consts::LN_10),
+            )
+        },
+        x0,
+    );
+    sol.value.powi(-2)
+}
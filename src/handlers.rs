@@ -1,5 +1,19 @@
+use crate::aga8::{Aga8, Aga8Method, Detail, Gross};
+use crate::choke::{self, Choke, ChokeFlow};
+use crate::composition::{self, ComponentId, Mixture, PlusFraction};
+use crate::datum::{self, Contact, GasColumn};
+use crate::deliverability;
+use crate::eos::{Eos, Phase};
+use crate::erosion;
+use crate::liquid_loading;
+use crate::material_balance::{self, MbMethod, MbPoint, WaterDrive};
 use crate::models::*;
-use crate::pressure;
+use crate::multiphase;
+use crate::pressure::{self, BhpMethod, CalcError};
+use crate::pseudo_critical::{self, PcMethod, SourCorrection};
+use crate::pseudo_pressure::{self, Gas};
+use crate::solver::Solution;
+use crate::trajectory::{SurveyStation, Trajectory, TrajectoryPoint};
 use axum::{
     http::StatusCode,
     response::{IntoResponse, Json},
@@ -27,7 +41,11 @@ pub async fn login_handler(Json(req): Json<LoginReq>) -> Result<&'static str, St
 pub async fn get_well_data_handler(
     Json(req): Json<WellDataReq>,
 ) -> Result<Json<Vec<WellData>>, StatusCode> {
-    let well_no = &req.well_no;
+    Ok(Json(load_well_data(&req.well_no, req.pc_method).await?))
+}
+
+// 读取 gaswell 表中的井数据，pc_method 为表中未录入拟临界参数时的计算方法
+async fn load_well_data(well_no: &str, pc_method: PcMethod) -> Result<Vec<WellData>, StatusCode> {
     let rows = sqlx::query!(
         "SELECT wellname, md, th, tb, rg, pc, tc, n2, co2, h2s FROM gaswell WHERE wellname = ?",
         well_no
@@ -39,122 +57,911 @@ pub async fn get_well_data_handler(
     if rows.is_empty() {
         return Err(StatusCode::NOT_FOUND);
     }
+    let trajectory = load_trajectory(well_no).await?;
 
     let well_data: Vec<WellData> = rows
         .into_iter()
-        .map(|row| WellData {
-            wellname: row.wellname,
-            md: row.md.unwrap_or_default(),
-            th: row.th.unwrap_or_default(),
-            tb: row.tb.unwrap_or_default(),
-            rg: row.rg.unwrap_or_default(),
-            pc: row.pc.unwrap_or_default(),
-            tc: row.tc.unwrap_or_default(),
-            n2: row.n2.unwrap_or_default(),
-            co2: row.co2.unwrap_or_default(),
-            h2s: row.h2s.unwrap_or_default(),
+        .map(|row| {
+            let rg = row.rg.unwrap_or_default();
+            let n2 = row.n2.unwrap_or_default();
+            let co2 = row.co2.unwrap_or_default();
+            let h2s = row.h2s.unwrap_or_default();
+            // 表中未录入拟临界参数时由相对密度计算，相对密度也未录入时为 None
+            let (pc, tc) = resolve_pc_tc(
+                row.pc.filter(|&v| v > 0.0),
+                row.tc.filter(|&v| v > 0.0),
+                Some(rg).filter(|&v| v > 0.0),
+                n2,
+                co2,
+                h2s,
+                pc_method,
+                SourCorrection::None,
+            )
+            .ok()
+            .unzip();
+            let md = row.md.unwrap_or_default();
+            WellData {
+                wellname: row.wellname,
+                md,
+                tvd: trajectory.tvd(md),
+                th: row.th.unwrap_or_default(),
+                tb: row.tb.unwrap_or_default(),
+                rg,
+                pc,
+                tc,
+                n2,
+                co2,
+                h2s,
+            }
         })
         .collect();
 
-    Ok(Json(well_data))
+    Ok(well_data)
+}
+
+// ============ 井眼轨迹 ============
+pub async fn get_trajectory_handler(
+    Json(req): Json<WellDataReq>,
+) -> Result<Json<Vec<TrajectoryPoint>>, StatusCode> {
+    let trajectory = load_trajectory(&req.well_no).await?;
+    Ok(Json(trajectory.points().to_vec()))
+}
+
+pub async fn save_trajectory_handler(
+    Json(req): Json<SaveTrajectoryReq>,
+) -> Result<Json<Vec<TrajectoryPoint>>, StatusCode> {
+    let trajectory = Trajectory::minimum_curvature(&req.stations)
+        .map_err(|_| StatusCode::UNPROCESSABLE_ENTITY)?;
+    store_survey(&req.well_no, &req.stations).await?;
+    Ok(Json(trajectory.points().to_vec()))
+}
+
+// 读取井的测斜数据并计算井眼轨迹，无测斜数据（或尚未建 well_trajectory 表）时按直井处理
+// well_trajectory 为可选的升级表（见 README），使用运行时查询，编译时不依赖该表
+async fn load_trajectory(well_no: &str) -> Result<Trajectory, StatusCode> {
+    let rows = match sqlx::query_as::<_, (f64, f64, f64)>(
+        "SELECT md, inc, azi FROM well_trajectory WHERE wellname = ? ORDER BY md",
+    )
+    .bind(well_no)
+    .fetch_all(crate::db::get_pool())
+    .await
+    {
+        Ok(rows) => rows,
+        Err(e) if is_missing_table(&e) => return Ok(Trajectory::vertical()),
+        Err(_) => return Err(StatusCode::INTERNAL_SERVER_ERROR),
+    };
+
+    let stations: Vec<SurveyStation> = rows
+        .into_iter()
+        .map(|(md, inc, azi)| SurveyStation { md, inc, azi })
+        .collect();
+    Trajectory::minimum_curvature(&stations).map_err(|_| StatusCode::UNPROCESSABLE_ENTITY)
+}
+
+// 表不存在（MySQL SQLSTATE 42S02）
+fn is_missing_table(e: &sqlx::Error) -> bool {
+    matches!(e, sqlx::Error::Database(db) if db.code().as_deref() == Some("42S02"))
+}
+
+// 以新的测斜数据替换井的原有数据
+async fn store_survey(well_no: &str, stations: &[SurveyStation]) -> Result<(), StatusCode> {
+    let mut tx = crate::db::get_pool()
+        .begin()
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    sqlx::query("DELETE FROM well_trajectory WHERE wellname = ?")
+        .bind(well_no)
+        .execute(&mut *tx)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    for s in stations {
+        sqlx::query("INSERT INTO well_trajectory (wellname, md, inc, azi) VALUES (?, ?, ?, ?)")
+            .bind(well_no)
+            .bind(s.md)
+            .bind(s.inc)
+            .bind(s.azi)
+            .execute(&mut *tx)
+            .await
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    }
+    tx.commit()
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
+}
+
+// 请求中给出井号时读取其井眼轨迹，否则按直井处理
+async fn trajectory_for(well_no: Option<&str>) -> Result<Trajectory, StatusCode> {
+    match well_no {
+        Some(well_no) => load_trajectory(well_no).await,
+        None => Ok(Trajectory::vertical()),
+    }
 }
 
 // ============ 单个计算处理 ============
 pub async fn calculate_z_handler(
     Json(req): Json<CalculateZReq>,
-) -> Result<Json<Vec<f64>>, StatusCode> {
-    let z_values: Vec<f64> = req
+) -> Result<Json<PropertyResp<ZResp>>, StatusCode> {
+    let (pc, tc) = resolve_pc_tc(
+        req.pc,
+        req.tc,
+        req.rg,
+        req.n2,
+        req.co2,
+        req.h2s,
+        req.pc_method,
+        req.sour_correction,
+    )?;
+    check_pressures(&req.pressures)?;
+    let z_values: Vec<ZResp> = req
         .pressures
         .par_iter()
-        .map(|&p| pressure::z(req.pc, req.tc, req.t, p))
+        .map(|&p| {
+            let sol = pressure::z_solve(req.z_method, pc, tc, req.t, p);
+            ZResp {
+                z: sol.value,
+                iterations: sol.iterations,
+                residual: sol.residual,
+                converged: sol.converged,
+            }
+        })
         .collect();
-    Ok(Json(z_values))
+    Ok(Json(PropertyResp {
+        values: z_values,
+        z_method: req.z_method.correlation().name(),
+        pc,
+        tc,
+    }))
 }
 
 pub async fn calculate_bg_handler(
     Json(req): Json<CalculateBgReq>,
-) -> Result<Json<Vec<f64>>, StatusCode> {
-    let bg_values: Vec<f64> = req
+) -> Result<Json<PropertyResp<Solution>>, StatusCode> {
+    let (pc, tc) = resolve_pc_tc(
+        req.pc,
+        req.tc,
+        req.rg,
+        req.n2,
+        req.co2,
+        req.h2s,
+        req.pc_method,
+        req.sour_correction,
+    )?;
+    check_pressures(&req.pressures)?;
+    let bg_values: Vec<Solution> = req
         .pressures
         .par_iter()
-        .map(|&p| pressure::bg(req.pc, req.tc, req.t, p))
+        .map(|&p| pressure::bg(req.z_method, pc, tc, req.t, p))
         .collect();
-    Ok(Json(bg_values))
+    Ok(Json(PropertyResp {
+        values: bg_values,
+        z_method: req.z_method.correlation().name(),
+        pc,
+        tc,
+    }))
 }
 
 pub async fn calculate_cg_handler(
     Json(req): Json<CalculateCgReq>,
-) -> Result<Json<Vec<f64>>, StatusCode> {
-    let cg_values: Vec<f64> = req
+) -> Result<Json<PropertyResp<Solution>>, StatusCode> {
+    let (pc, tc) = resolve_pc_tc(
+        req.pc,
+        req.tc,
+        req.rg,
+        req.n2,
+        req.co2,
+        req.h2s,
+        req.pc_method,
+        req.sour_correction,
+    )?;
+    check_pressures(&req.pressures)?;
+    let cg_values: Vec<Solution> = req
         .pressures
         .par_iter()
-        .map(|&p| pressure::cg(req.pc, req.tc, req.t, p))
+        .map(|&p| pressure::cg(req.z_method, pc, tc, req.t, p))
         .collect();
-    Ok(Json(cg_values))
+    Ok(Json(PropertyResp {
+        values: cg_values,
+        z_method: req.z_method.correlation().name(),
+        pc,
+        tc,
+    }))
 }
 
 pub async fn calculate_density_handler(
     Json(req): Json<CalculateDensityReq>,
-) -> Result<Json<Vec<f64>>, StatusCode> {
-    let density_values: Vec<f64> = req
+) -> Result<Json<PropertyResp<Solution>>, StatusCode> {
+    let (pc, tc) = resolve_pc_tc(
+        req.pc,
+        req.tc,
+        Some(req.rg),
+        req.n2,
+        req.co2,
+        req.h2s,
+        req.pc_method,
+        req.sour_correction,
+    )?;
+    check_pressures(&req.pressures)?;
+    let density_values: Vec<Solution> = req
         .pressures
         .par_iter()
-        .map(|&p| pressure::density(req.rg, req.pc, req.tc, req.t, p))
+        .map(|&p| pressure::density(req.z_method, req.rg, pc, tc, req.t, p))
         .collect();
-    Ok(Json(density_values))
+    Ok(Json(PropertyResp {
+        values: density_values,
+        z_method: req.z_method.correlation().name(),
+        pc,
+        tc,
+    }))
 }
 
 pub async fn calculate_niandu_handler(
     Json(req): Json<CalculateNianduReq>,
-) -> Result<Json<Vec<f64>>, StatusCode> {
-    let niandu_values: Vec<f64> = req
+) -> Result<Json<PropertyResp<Solution>>, StatusCode> {
+    let (pc, tc) = resolve_pc_tc(
+        req.pc,
+        req.tc,
+        Some(req.rg),
+        req.n2,
+        req.co2,
+        req.h2s,
+        req.pc_method,
+        req.sour_correction,
+    )?;
+    check_pressures(&req.pressures)?;
+    let niandu_values: Vec<Solution> = req
         .pressures
         .par_iter()
-        .map(|&p| pressure::niandu(req.rg, req.pc, req.tc, req.t, p, req.n2, req.co2, req.h2s))
+        .map(|&p| {
+            pressure::niandu(
+                req.z_method,
+                req.rg,
+                pc,
+                tc,
+                req.t,
+                p,
+                req.n2,
+                req.co2,
+                req.h2s,
+            )
+        })
         .collect();
-    Ok(Json(niandu_values))
+    Ok(Json(PropertyResp {
+        values: niandu_values,
+        z_method: req.z_method.correlation().name(),
+        pc,
+        tc,
+    }))
 }
 
 pub async fn calculate_pwbs_handler(
     Json(req): Json<CalculatePwbsReq>,
 ) -> Result<impl IntoResponse, StatusCode> {
-    let pwbs_value = pressure::pwbs(req.rg, req.pc, req.tc, req.h, req.tts, req.tws, req.pts);
-    Ok(Json(vec![pwbs_value]))
+    let (pc, tc) = resolve_pc_tc(
+        req.pc,
+        req.tc,
+        Some(req.rg),
+        req.n2,
+        req.co2,
+        req.h2s,
+        req.pc_method,
+        req.sour_correction,
+    )?;
+    let method = req.method.unwrap_or(BhpMethod::AverageTz);
+    let tvd = trajectory_for(req.well_no.as_deref()).await?.tvd(req.h);
+    let sol = pressure::pws_by(
+        method,
+        req.z_method,
+        req.rg,
+        pc,
+        tc,
+        tvd,
+        req.tts,
+        req.tws,
+        req.pts,
+    )
+    .map_err(|_| StatusCode::UNPROCESSABLE_ENTITY)?;
+    Ok(Json(vec![PwbsResp {
+        pwbs: sol.value,
+        method: method.name(),
+        iterations: sol.iterations,
+        residual: sol.residual,
+        converged: sol.converged,
+    }]))
+}
+
+// ============ 井筒压力剖面 ============
+pub async fn calculate_traverse_handler(
+    Json(req): Json<CalculateTraverseReq>,
+) -> Result<Json<TraverseResp>, StatusCode> {
+    let (pc, tc) = resolve_pc_tc(
+        req.pc,
+        req.tc,
+        Some(req.rg),
+        req.n2,
+        req.co2,
+        req.h2s,
+        req.pc_method,
+        req.sour_correction,
+    )?;
+    pressure::check_fractions(req.n2, req.co2, req.h2s).map_err(|_| StatusCode::BAD_REQUEST)?;
+    let gas = pressure::GasProps {
+        method: req.z_method,
+        rg: req.rg,
+        pc,
+        tc,
+        yn2: req.n2,
+        yco2: req.co2,
+        yh2s: req.h2s,
+    };
+    let trajectory = trajectory_for(req.well_no.as_deref()).await?;
+    // 未给出管柱时按静气柱计算（管径不参与计算），流动剖面须给出管柱
+    let sections = match (req.sections.is_empty(), req.h) {
+        (false, _) => req.sections,
+        (true, Some(h)) if req.qg == 0.0 => vec![pressure::TubingSection { d: 1.0, h }],
+        _ => return Err(StatusCode::BAD_REQUEST),
+    };
+
+    let (points, sol) = tokio::task::spawn_blocking(move || {
+        pressure::traverse(
+            &gas,
+            &sections,
+            &trajectory,
+            &req.temperature,
+            req.ee,
+            req.friction,
+            req.tts,
+            req.tws,
+            req.pts,
+            req.qg,
+            req.step,
+        )
+    })
+    .await
+    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+    .map_err(|_| StatusCode::UNPROCESSABLE_ENTITY)?;
+
+    Ok(Json(TraverseResp {
+        points,
+        temperature: req.temperature.name(),
+        z_method: req.z_method.correlation().name(),
+        pc,
+        tc,
+        iterations: sol.iterations,
+        residual: sol.residual,
+        converged: sol.converged,
+    }))
+}
+
+// ============ 气液两相流 ============
+pub async fn calculate_multiphase_handler(
+    Json(req): Json<CalculateMultiphaseReq>,
+) -> Result<Json<MultiphaseResp>, StatusCode> {
+    let (pc, tc) = resolve_pc_tc(
+        req.pc,
+        req.tc,
+        Some(req.rg),
+        req.n2,
+        req.co2,
+        req.h2s,
+        req.pc_method,
+        req.sour_correction,
+    )?;
+    pressure::check_fractions(req.n2, req.co2, req.h2s).map_err(|_| StatusCode::BAD_REQUEST)?;
+    let gas = pressure::GasProps {
+        method: req.z_method,
+        rg: req.rg,
+        pc,
+        tc,
+        yn2: req.n2,
+        yco2: req.co2,
+        yh2s: req.h2s,
+    };
+    let trajectory = trajectory_for(req.well_no.as_deref()).await?;
+    let (method, temperature) = (req.method, req.temperature);
+
+    let (points, sol) = tokio::task::spawn_blocking(move || {
+        multiphase::traverse(
+            &gas,
+            &req.liquids,
+            method,
+            &req.sections,
+            &trajectory,
+            &temperature,
+            req.ee,
+            req.friction,
+            req.tts,
+            req.tws,
+            req.pts,
+            req.qg,
+            req.step,
+        )
+    })
+    .await
+    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+    .map_err(|_| StatusCode::UNPROCESSABLE_ENTITY)?;
+
+    Ok(Json(MultiphaseResp {
+        pwf: sol.value,
+        points,
+        method: method.name(),
+        temperature: temperature.name(),
+        z_method: gas.method.correlation().name(),
+        pc,
+        tc,
+        iterations: sol.iterations,
+        residual: sol.residual,
+        converged: sol.converged,
+    }))
 }
 
 // ============ 批量PVT计算 ============
 pub async fn calculate_batch_pvt_handler(
     Json(req): Json<CalculateBatchPVTReq>,
-) -> Result<Json<Vec<BatchPVTResp>>, StatusCode> {
+) -> Result<Json<Vec<BatchRow<BatchPVTResp>>>, StatusCode> {
+    let (pc, tc) = resolve_pc_tc(
+        req.pc,
+        req.tc,
+        Some(req.rg),
+        req.n2,
+        req.co2,
+        req.h2s,
+        req.pc_method,
+        req.sour_correction,
+    )?;
     let pressures = req.pressures.clone();
-    let pc = req.pc;
-    let tc = req.tc;
     let t = req.t;
     let rg = req.rg;
     let n2 = req.n2;
     let co2 = req.co2;
     let h2s = req.h2s;
+    let z_method = req.z_method;
+    let aga8 = match req.aga8 {
+        Some(method) => Some(build_aga8(
+            method,
+            req.components.as_deref(),
+            Some(rg),
+            n2,
+            co2,
+        )?),
+        None => None,
+    };
+    let method_name = match &aga8 {
+        Some(gas) => gas.name(),
+        None => z_method.correlation().name(),
+    };
+    let c_factor = req.c_factor.unwrap_or(erosion::DEFAULT_C);
+
+    let fractions = pressure::check_fractions(n2, co2, h2s);
 
-    let result: Vec<BatchPVTResp> = tokio::task::spawn_blocking(move || {
+    let result: Vec<BatchRow<BatchPVTResp>> = tokio::task::spawn_blocking(move || {
         pressures
             .into_par_iter()
-            .map(|p| {
-                let z = pressure::z(pc, tc, t, p);
-                let p_over_z = if z != 0.0 { p / z } else { 0.0 };
+            .map(|p| -> Result<_, CalcError> {
+                fractions?;
+                pressure::check_pressure(p)?;
+                let (sol, density, cg) = match &aga8 {
+                    Some(gas) => {
+                        let sol = pressure::check_converged(gas.z_solve(t, p))?;
+                        // AGA8 密度为 kg/m³，统一换算为 g/cm³
+                        (sol, gas.density(t, p) / 1000.0, gas.cg(t, p))
+                    }
+                    None => {
+                        let sol = pressure::z_checked(z_method, pc, tc, t, p)?;
+                        let density = 3.4844 * p * rg / (sol.value * t);
+                        (sol, density, pressure::cg(z_method, pc, tc, t, p).value)
+                    }
+                };
+                let z = sol.value;
+                let p_over_z = p / z;
                 let bg = 0.0003447 * z * t / p;
-                let density = 3.4844 * p * rg / (z * t);
                 let niandu = calculate_viscosity(rg, t, density, n2, co2, h2s);
-                let cg = pressure::cg(pc, tc, t, p);
 
-                BatchPVTResp {
+                Ok(BatchPVTResp {
                     z,
                     p_over_z,
                     bg,
                     niandu,
                     cg,
                     density,
-                }
+                    ve: erosion::erosional_velocity(c_factor, density * 1000.0),
+                    z_method: method_name,
+                    pc,
+                    tc,
+                    iterations: sol.iterations,
+                    residual: sol.residual,
+                    converged: sol.converged,
+                })
             })
+            .map(BatchRow::from)
+            .collect()
+    })
+    .await
+    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    Ok(Json(result))
+}
+
+// ============ 冲蚀流速校核 ============
+pub async fn calculate_erosion_handler(
+    Json(req): Json<CalculateErosionReq>,
+) -> Result<Json<Vec<ErosionResp>>, StatusCode> {
+    let (pc, tc) = resolve_pc_tc(
+        req.pc,
+        req.tc,
+        Some(req.rg),
+        req.n2,
+        req.co2,
+        req.h2s,
+        req.pc_method,
+        req.sour_correction,
+    )?;
+    pressure::check_fractions(req.n2, req.co2, req.h2s).map_err(|_| StatusCode::BAD_REQUEST)?;
+    if req.d <= 0.0 || req.qg < 0.0 {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    let c_factor = req.c_factor.unwrap_or(erosion::DEFAULT_C);
+
+    let check = |location: &'static str, p: f64, t: f64| -> Result<ErosionResp, CalcError> {
+        pressure::check_pressure(p)?;
+        let z = pressure::z_checked(req.z_method, pc, tc, t, p)?.value;
+        let density = pressure::density(req.z_method, req.rg, pc, tc, t, p).value * 1000.0;
+        let v = liquid_loading::velocity_from_rate(req.d, p, t, z, req.qg);
+        let ve = erosion::erosional_velocity(c_factor, density);
+        Ok(ErosionResp {
+            location,
+            p,
+            t,
+            z,
+            density,
+            v,
+            ve,
+            qe: liquid_loading::rate_from_velocity(req.d, p, t, z, ve),
+            exceeded: v > ve,
+        })
+    };
+
+    let result = [("井口", req.pt, req.tt), ("井底", req.pwf, req.twf)]
+        .into_iter()
+        .map(|(location, p, t)| check(location, p, t))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| StatusCode::UNPROCESSABLE_ENTITY)?;
+
+    Ok(Json(result))
+}
+
+// ============ 物质平衡 ============
+pub async fn calculate_material_balance_handler(
+    Json(req): Json<CalculateMaterialBalanceReq>,
+) -> Result<Json<MaterialBalanceResp>, StatusCode> {
+    let (pc, tc) = resolve_pc_tc(
+        req.pc,
+        req.tc,
+        Some(req.rg),
+        req.n2,
+        req.co2,
+        req.h2s,
+        req.pc_method,
+        req.sour_correction,
+    )?;
+    pressure::check_fractions(req.n2, req.co2, req.h2s).map_err(|_| StatusCode::BAD_REQUEST)?;
+    let (z_method, method, t) = (req.z_method, req.method, req.t);
+    // 原始地层压力缺省取累产最小的点
+    let pi = match req.pi {
+        Some(pi) => pi,
+        None => req
+            .history
+            .iter()
+            .min_by(|a, b| a.gp.total_cmp(&b.gp))
+            .map(|h| h.p)
+            .ok_or(StatusCode::BAD_REQUEST)?,
+    };
+    let pz = |history: &[MbPoint]| material_balance::p_over_z(z_method, pc, tc, t, history);
+
+    let (initial, points) = pz(&[MbPoint { p: pi, gp: 0.0 }])
+        .and_then(|initial| Ok((initial[0], pz(&req.history)?)))
+        .map_err(|_| StatusCode::UNPROCESSABLE_ENTITY)?;
+    let analysis = material_balance::analyze(method, &req.rock, &initial, points.clone())
+        .map_err(|_| StatusCode::UNPROCESSABLE_ENTITY)?;
+    // 异常高压方法同时给出未经校正的 p/Z 外推储量，供对比
+    let apparent_ogip = match method {
+        MbMethod::POverZ => None,
+        _ => material_balance::analyze(MbMethod::POverZ, &req.rock, &initial, points)
+            .ok()
+            .map(|a| a.ogip),
+    };
+    let abandonment = match req.pa {
+        Some(pa) => {
+            let p_over_z_a = pz(&[MbPoint { p: pa, gp: 0.0 }])
+                .map_err(|_| StatusCode::UNPROCESSABLE_ENTITY)?[0]
+                .p_over_z;
+            let (gp_a, rf) = analysis.recovery(pi, pa, p_over_z_a);
+            Some((p_over_z_a, gp_a, rf))
+        }
+        None => None,
+    };
+
+    Ok(Json(MaterialBalanceResp {
+        ogip: analysis.ogip,
+        apparent_ogip,
+        pi,
+        p_over_z_i: analysis.p_over_z_i,
+        ce: analysis.ce,
+        fit: analysis.fit,
+        p_over_z_a: abandonment.map(|(pz, _, _)| pz),
+        gp_a: abandonment.map(|(_, gp, _)| gp),
+        recovery: abandonment.map(|(_, _, rf)| rf),
+        points: analysis.points,
+        line: analysis.line,
+        method: method.name(),
+        z_method: z_method.correlation().name(),
+        pc,
+        tc,
+    }))
+}
+
+// ============ 水驱气藏物质平衡 ============
+pub async fn calculate_water_drive_handler(
+    Json(req): Json<CalculateWaterDriveReq>,
+) -> Result<Json<WaterDriveResp>, StatusCode> {
+    let (pc, tc) = resolve_pc_tc(
+        req.pc,
+        req.tc,
+        Some(req.rg),
+        req.n2,
+        req.co2,
+        req.h2s,
+        req.pc_method,
+        req.sour_correction,
+    )?;
+    pressure::check_fractions(req.n2, req.co2, req.h2s).map_err(|_| StatusCode::BAD_REQUEST)?;
+    let (z_method, t) = (req.z_method, req.t);
+    let pi = match req.pi {
+        Some(pi) => pi,
+        None => req.history.first().ok_or(StatusCode::BAD_REQUEST)?.p,
+    };
+    let bg = |p: f64| -> Result<f64, CalcError> {
+        pressure::z_checked(z_method, pc, tc, t, p)?;
+        Ok(pressure::bg(z_method, pc, tc, t, p).value)
+    };
+    let (bgi, bgs) = bg(pi)
+        .and_then(|bgi| {
+            let bgs = req
+                .history
+                .iter()
+                .map(|h| bg(h.p))
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok((bgi, bgs))
+        })
+        .map_err(|_| StatusCode::UNPROCESSABLE_ENTITY)?;
+    let drive = WaterDrive {
+        model: req.model,
+        aquifer: req.aquifer,
+        rd: req.rd,
+        td_coef: req.td_coef,
+        pi,
+        bgi,
+        bw: req.bw.unwrap_or(1.0),
+    };
+    let (rock, history) = (req.rock, req.history);
+
+    let analysis = tokio::task::spawn_blocking(move || {
+        material_balance::water_drive(&drive, &rock, &history, &bgs)
+    })
+    .await
+    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+    .map_err(|_| StatusCode::UNPROCESSABLE_ENTITY)?;
+
+    Ok(Json(WaterDriveResp {
+        ogip: analysis.ogip,
+        volumetric_ogip: analysis.volumetric_ogip,
+        params: analysis.params,
+        r2: analysis.r2,
+        pi,
+        bgi,
+        rows: analysis.rows,
+        model: drive.model.name(),
+        aquifer: drive.aquifer.name(),
+        z_method: z_method.correlation().name(),
+        pc,
+        tc,
+    }))
+}
+
+// ============ 压力折算到基准面 ============
+pub async fn calculate_datum_handler(
+    Json(req): Json<CalculateDatumReq>,
+) -> Result<Json<Vec<BatchRow<DatumResp>>>, StatusCode> {
+    let (pc, tc) = resolve_pc_tc(
+        req.pc,
+        req.tc,
+        Some(req.rg),
+        req.n2,
+        req.co2,
+        req.h2s,
+        req.pc_method,
+        req.sour_correction,
+    )?;
+    let gas = GasColumn {
+        method: req.z_method,
+        rg: req.rg,
+        pc,
+        tc,
+    };
+    let contact = req.contact.map(|elevation| Contact {
+        elevation,
+        density: req.rho_l.unwrap_or(req.liquid.density()),
+    });
+    let t_grad = req.t_grad.unwrap_or(3.0) / 100.0;
+    let trajectory = trajectory_for(req.well_no.as_deref()).await?;
+    let fractions = pressure::check_fractions(req.n2, req.co2, req.h2s);
+
+    let result = req
+        .readings
+        .iter()
+        .map(|r| -> Result<_, CalcError> {
+            fractions?;
+            let tvd = trajectory.tvd(r.md);
+            let gauge_elevation = req.elevation - tvd;
+            let d = datum::to_datum(&gas, r.p, gauge_elevation, r.t, t_grad, req.datum, contact)?;
+            Ok(DatumResp {
+                md: r.md,
+                tvd,
+                gauge_elevation,
+                p_gauge: r.p,
+                p_datum: d.p,
+                t_datum: d.t,
+                gas_column: d.gas_dh,
+                liquid_column: d.liquid_dh,
+                z_method: gas.method.correlation().name(),
+                pc,
+                tc,
+                iterations: d.sol.iterations,
+                residual: d.sol.residual,
+                converged: d.sol.converged,
+            })
+        })
+        .map(BatchRow::from)
+        .collect();
+
+    Ok(Json(result))
+}
+
+// ============ 摩阻系数计算 ============
+pub async fn calculate_friction_handler(
+    Json(req): Json<CalculateFrictionReq>,
+) -> Result<Json<Vec<BatchRow<FrictionResp>>>, StatusCode> {
+    let (pc, tc) = resolve_pc_tc(
+        req.pc,
+        req.tc,
+        Some(req.rg),
+        req.n2,
+        req.co2,
+        req.h2s,
+        req.pc_method,
+        req.sour_correction,
+    )?;
+    if req.d <= 0.0 || req.ee < 0.0 {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    let gas = pressure::GasProps {
+        method: req.z_method,
+        rg: req.rg,
+        pc,
+        tc,
+        yn2: req.n2,
+        yco2: req.co2,
+        yh2s: req.h2s,
+    };
+    let (d, ee, method) = (req.d, req.ee, req.method);
+    let fractions = pressure::check_fractions(gas.yn2, gas.yco2, gas.yh2s);
+
+    let result = req
+        .points
+        .into_iter()
+        .map(|pt| -> Result<_, CalcError> {
+            fractions?;
+            pressure::check_pressure(pt.p)?;
+            if pt.qg < 0.0 {
+                return Err(CalcError::InvalidInput("产气量不能为负"));
+            }
+            let z = pressure::z_checked(gas.method, pc, tc, pt.t, pt.p)?.value;
+            let re = gas.reynolds(pt.t, pt.p, z, pt.qg, d);
+            Ok(FrictionResp {
+                z,
+                niandu: gas.mu(pt.t, pt.p, z),
+                re,
+                f: method.factor(re, ee, d),
+                method: method.name(),
+                z_method: gas.method.correlation().name(),
+                pc,
+                tc,
+            })
+        })
+        .map(BatchRow::from)
+        .collect();
+
+    Ok(Json(result))
+}
+
+// ============ 油嘴流动计算 ============
+/// 已知嘴前、嘴后压力和油嘴直径求产气量
+pub async fn calculate_choke_rate_handler(
+    Json(req): Json<CalculateChokeReq<ChokeRatePoint>>,
+) -> Result<Json<Vec<BatchRow<ChokeResp>>>, StatusCode> {
+    choke_batch(req, |c, rg, z, pt: ChokeRatePoint| {
+        c.rate(rg, z, pt.t1, pt.p1, pt.p2, pt.d)
+    })
+    .await
+}
+
+/// 已知嘴前、嘴后压力和目标产气量求所需油嘴直径
+pub async fn calculate_choke_size_handler(
+    Json(req): Json<CalculateChokeReq<ChokeSizePoint>>,
+) -> Result<Json<Vec<BatchRow<ChokeResp>>>, StatusCode> {
+    choke_batch(req, |c, rg, z, pt: ChokeSizePoint| {
+        c.size(rg, z, pt.t1, pt.p1, pt.p2, pt.qg)
+    })
+    .await
+}
+
+/// 油嘴批量计算：逐点按嘴前条件求 Z，再由 solve 计算产气量或油嘴直径
+async fn choke_batch<P, F>(
+    req: CalculateChokeReq<P>,
+    solve: F,
+) -> Result<Json<Vec<BatchRow<ChokeResp>>>, StatusCode>
+where
+    P: ChokeInlet + Copy + Send + Sync + 'static,
+    F: Fn(&Choke, f64, f64, P) -> Result<ChokeFlow, CalcError> + Send + Sync + 'static,
+{
+    let (pc, tc) = resolve_pc_tc(
+        req.pc,
+        req.tc,
+        Some(req.rg),
+        req.n2,
+        req.co2,
+        req.h2s,
+        req.pc_method,
+        req.sour_correction,
+    )?;
+    let choke = Choke {
+        method: req.method,
+        k: req.k.unwrap_or(choke::DEFAULT_K),
+        cd: req.cd.unwrap_or(choke::DEFAULT_CD),
+    };
+    let (rg, z_method) = (req.rg, req.z_method);
+    let fractions = pressure::check_fractions(req.n2, req.co2, req.h2s);
+    let points = req.points;
+
+    let result = tokio::task::spawn_blocking(move || {
+        points
+            .into_par_iter()
+            .map(|pt| -> Result<_, CalcError> {
+                fractions?;
+                let (p1, t1) = pt.inlet();
+                pressure::check_pressure(p1)?;
+                let sol = pressure::z_checked(z_method, pc, tc, t1, p1)?;
+                let flow = solve(&choke, rg, sol.value, pt)?;
+                Ok(ChokeResp {
+                    qg: flow.qg,
+                    d: flow.d,
+                    z: sol.value,
+                    ratio: flow.ratio,
+                    critical_ratio: choke.critical_ratio(),
+                    regime: if flow.critical {
+                        "临界流"
+                    } else {
+                        "亚临界流"
+                    },
+                    method: choke.method.name(),
+                    z_method: z_method.correlation().name(),
+                    pc,
+                    tc,
+                    iterations: sol.iterations,
+                    residual: sol.residual,
+                    converged: sol.converged,
+                })
+            })
+            .map(BatchRow::from)
             .collect()
     })
     .await
@@ -166,38 +973,307 @@ pub async fn calculate_batch_pvt_handler(
 // ============ 批量PB计算 ============
 pub async fn calculate_batch_pb_handler(
     Json(req): Json<CalculateBatchPbReq>,
-) -> Result<Json<Vec<BatchPbResp>>, StatusCode> {
+) -> Result<Json<Vec<BatchRow<BatchPbResp>>>, StatusCode> {
+    let (pc, tc) = resolve_pc_tc(
+        req.pc,
+        req.tc,
+        Some(req.rg),
+        req.n2,
+        req.co2,
+        req.h2s,
+        req.pc_method,
+        req.sour_correction,
+    )?;
     let pts = req.pts.clone();
     let rg = req.rg;
-    let pc = req.pc;
-    let tc = req.tc;
-    let h = req.h;
+    let h = trajectory_for(req.well_no.as_deref()).await?.tvd(req.h); // 垂深
     let tts = req.tts;
     let tws = req.tws;
     let n2 = req.n2;
     let co2 = req.co2;
     let h2s = req.h2s;
+    let z_method = req.z_method;
+    let method = req.method.unwrap_or(BhpMethod::AverageTz);
+
+    let fractions = pressure::check_fractions(n2, co2, h2s);
 
     let result = tokio::task::spawn_blocking(move || {
         pts.into_par_iter()
-            .map(|pt| {
-                let pwbs = pressure::pws(rg, pc, tc, h, tts, tws, pt);
-                let z = pressure::z(pc, tc, tws, pwbs);
-                let p_over_z = if z != 0.0 { pwbs / z } else { 0.0 };
+            .map(|pt| -> Result<_, CalcError> {
+                fractions?;
+                pressure::check_pressure(pt)?;
+                let sol = pressure::check_converged(pressure::pws_by(
+                    method, z_method, rg, pc, tc, h, tts, tws, pt,
+                )?)?;
+                let pwbs = sol.value;
+                let z = pressure::z_checked(z_method, pc, tc, tws, pwbs)?.value;
+                let p_over_z = pwbs / z;
                 let bg = 0.0003447 * z * tws / pwbs;
                 let density = 3.4844 * pwbs * rg / (z * tws);
                 let niandu = calculate_viscosity(rg, tws, density, n2, co2, h2s);
-                let cg = pressure::cg(pc, tc, tws, pwbs);
+                let cg = pressure::cg(z_method, pc, tc, tws, pwbs).value;
 
-                BatchPbResp {
+                Ok(BatchPbResp {
                     pwbs,
                     z,
                     p_over_z,
                     bg,
                     niandu,
                     cg,
-                }
+                    method: method.name(),
+                    z_method: z_method.correlation().name(),
+                    pc,
+                    tc,
+                    iterations: sol.iterations,
+                    residual: sol.residual,
+                    converged: sol.converged,
+                })
+            })
+            .map(BatchRow::from)
+            .collect()
+    })
+    .await
+    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    Ok(Json(result))
+}
+
+// ============ 批量井底流压计算 ============
+pub async fn calculate_batch_pwf_handler(
+    Json(req): Json<CalculateBatchPwfReq>,
+) -> Result<Json<Vec<BatchRow<BatchPwfResp>>>, StatusCode> {
+    let (pc, tc) = resolve_pc_tc(
+        req.pc,
+        req.tc,
+        Some(req.rg),
+        req.n2,
+        req.co2,
+        req.h2s,
+        req.pc_method,
+        req.sour_correction,
+    )?;
+    let gas = pressure::GasProps {
+        method: req.z_method,
+        rg: req.rg,
+        pc,
+        tc,
+        yn2: req.n2,
+        yco2: req.co2,
+        yh2s: req.h2s,
+    };
+    let trajectory = trajectory_for(req.well_no.as_deref()).await?;
+    let temperature = req.temperature;
+    let points = req.points.clone();
+    let sections = req.sections.clone();
+    let (ee, friction) = (req.ee, req.friction);
+    let tts = req.tts;
+    let tws = req.tws;
+    let method = req.method;
+
+    let fractions = pressure::check_fractions(gas.yn2, gas.yco2, gas.yh2s);
+
+    let result = tokio::task::spawn_blocking(move || {
+        points
+            .into_par_iter()
+            .map(|point| -> Result<_, CalcError> {
+                fractions?;
+                let sol = pressure::pwf(
+                    &gas,
+                    method,
+                    &sections,
+                    &trajectory,
+                    &temperature,
+                    ee,
+                    friction,
+                    tts,
+                    tws,
+                    point.pt,
+                    point.qg,
+                )?;
+                let pwf = sol.value;
+                let z = pressure::z_checked(gas.method, pc, tc, tws, pwf)?.value;
+
+                Ok(BatchPwfResp {
+                    pt: point.pt,
+                    qg: point.qg,
+                    pwf,
+                    z,
+                    p_over_z: pwf / z,
+                    bg: 0.0003447 * z * tws / pwf,
+                    niandu: gas.mu(tws, pwf, z),
+                    cg: pressure::cg(gas.method, pc, tc, tws, pwf).value,
+                    method: method.name(),
+                    z_method: gas.method.correlation().name(),
+                    pc,
+                    tc,
+                    iterations: sol.iterations,
+                    residual: sol.residual,
+                    converged: sol.converged,
+                })
+            })
+            .map(BatchRow::from)
+            .collect()
+    })
+    .await
+    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    Ok(Json(result))
+}
+
+// ============ 积液分析 ============
+pub async fn calculate_liquid_loading_handler(
+    Json(req): Json<CalculateLiquidLoadingReq>,
+) -> Result<Json<Vec<BatchRow<LiquidLoadingResp>>>, StatusCode> {
+    let (pc, tc) = resolve_pc_tc(
+        req.pc,
+        req.tc,
+        Some(req.rg),
+        req.n2,
+        req.co2,
+        req.h2s,
+        req.pc_method,
+        req.sour_correction,
+    )?;
+    let rg = req.rg;
+    let d = req.d;
+    let z_method = req.z_method;
+    let model = req.model;
+    let rho_l = req.rho_l.unwrap_or(req.liquid.density());
+    let sigma = req.sigma.unwrap_or(req.liquid.surface_tension());
+
+    let fractions = pressure::check_fractions(req.n2, req.co2, req.h2s);
+    let tubing = if d > 0.0 {
+        Ok(())
+    } else {
+        Err(CalcError::InvalidInput("油管内径须大于 0"))
+    };
+
+    let result = tokio::task::spawn_blocking(move || {
+        req.records
+            .into_par_iter()
+            .map(|r| -> Result<_, CalcError> {
+                fractions?;
+                tubing?;
+                pressure::check_pressure(r.pt)?;
+                let z = pressure::z_checked(z_method, pc, tc, r.tt, r.pt)?.value;
+                // g/cm³ → kg/m³
+                let density = pressure::density(z_method, rg, pc, tc, r.tt, r.pt).value * 1000.0;
+                let vc = model.critical_velocity(rho_l, density, sigma);
+                let qc = liquid_loading::rate_from_velocity(d, r.pt, r.tt, z, vc);
+
+                Ok(LiquidLoadingResp {
+                    z,
+                    density,
+                    vc,
+                    qc,
+                    v: liquid_loading::velocity_from_rate(d, r.pt, r.tt, z, r.qg),
+                    loading: r.qg < qc,
+                    model: model.name(),
+                    well_no: r.well_no,
+                    date: r.date,
+                })
+            })
+            .map(BatchRow::from)
+            .collect()
+    })
+    .await
+    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    Ok(Json(result))
+}
+
+// ============ 油管尺寸优选 ============
+pub async fn calculate_tubing_study_handler(
+    Json(req): Json<CalculateTubingStudyReq>,
+) -> Result<Json<Vec<BatchRow<TubingStudyResp>>>, StatusCode> {
+    let well = load_well_data(&req.well_no, req.pc_method)
+        .await?
+        .into_iter()
+        .next()
+        .ok_or(StatusCode::NOT_FOUND)?;
+    let (pc, tc) = well
+        .pc
+        .zip(well.tc)
+        .ok_or(StatusCode::UNPROCESSABLE_ENTITY)?;
+    let (pc, tc) =
+        pseudo_critical::sour_correct(req.sour_correction, pc, tc, well.n2, well.co2, well.h2s);
+    let gas = pressure::GasProps {
+        method: req.z_method,
+        rg: well.rg,
+        pc,
+        tc,
+        yn2: well.n2,
+        yco2: well.co2,
+        yh2s: well.h2s,
+    };
+    let trajectory = load_trajectory(&req.well_no).await?;
+    let fractions = pressure::check_fractions(gas.yn2, gas.yco2, gas.yh2s);
+    let (pt, qg, ee, friction) = (req.pt, req.qg, req.ee, req.friction);
+    let (tts, tws, md) = (well.th, well.tb, well.md);
+    let (method, temperature, model) = (req.method, req.temperature, req.model);
+    let rho_l = req.rho_l.unwrap_or(req.liquid.density());
+    let sigma = req.sigma.unwrap_or(req.liquid.surface_tension());
+
+    let result = tokio::task::spawn_blocking(move || {
+        req.candidates
+            .into_par_iter()
+            .map(|c| -> Result<_, CalcError> {
+                fractions?;
+                pressure::check_pressure(pt)?;
+                let sections = [pressure::TubingSection { d: c.d, h: md }];
+                let sol = pressure::pwf(
+                    &gas,
+                    method,
+                    &sections,
+                    &trajectory,
+                    &temperature,
+                    ee,
+                    friction,
+                    tts,
+                    tws,
+                    pt,
+                    qg,
+                )?;
+                let pwf = sol.value;
+                // 同一井口压力、同一流动井温剖面下的静气柱压力，与流压之差即摩阻压降
+                let static_bhp = pressure::static_bhp(
+                    &gas,
+                    method,
+                    &sections,
+                    &trajectory,
+                    &temperature,
+                    ee,
+                    friction,
+                    tts,
+                    tws,
+                    pt,
+                    qg,
+                )?;
+
+                // 井口条件下的临界携液气量
+                let z_top = pressure::z_checked(gas.method, pc, tc, tts, pt)?.value;
+                let density = pressure::density(gas.method, gas.rg, pc, tc, tts, pt).value * 1000.0;
+                let vc = model.critical_velocity(rho_l, density, sigma);
+                let qc = liquid_loading::rate_from_velocity(c.d, pt, tts, z_top, vc);
+                let z_bottom = pressure::z_checked(gas.method, pc, tc, tws, pwf)?.value;
+
+                Ok(TubingStudyResp {
+                    d: c.d,
+                    pwf,
+                    friction_loss: pwf - static_bhp.value,
+                    v_top: liquid_loading::velocity_from_rate(c.d, pt, tts, z_top, qg),
+                    v_bottom: liquid_loading::velocity_from_rate(c.d, pwf, tws, z_bottom, qg),
+                    vc,
+                    qc,
+                    loading: qg < qc,
+                    model: model.name(),
+                    iterations: sol.iterations,
+                    residual: sol.residual,
+                    converged: sol.converged,
+                    name: c.name,
+                })
             })
+            .map(BatchRow::from)
             .collect()
     })
     .await
@@ -209,36 +1285,245 @@ pub async fn calculate_batch_pb_handler(
 // ============ 批量井口压力计算 ============
 pub async fn calculate_batch_ph_handler(
     Json(req): Json<CalculateBatchPhReq>,
-) -> Result<Json<Vec<BatchPhResp>>, StatusCode> {
+) -> Result<Json<Vec<BatchRow<BatchPhResp>>>, StatusCode> {
+    let (pc, tc) = resolve_pc_tc(
+        req.pc,
+        req.tc,
+        Some(req.rg),
+        req.n2,
+        req.co2,
+        req.h2s,
+        req.pc_method,
+        req.sour_correction,
+    )?;
     let pwbs = req.pwbs.clone();
     let rg = req.rg;
-    let pc = req.pc;
-    let tc = req.tc;
-    let h = req.h;
+    // 未给出井号时按直井处理
+    let well_no = Some(req.well_no.as_str()).filter(|w| !w.is_empty());
+    let h = trajectory_for(well_no).await?.tvd(req.h); // 垂深
     let tts = req.tts;
     let tws = req.tws;
     let n2 = req.n2;
     let co2 = req.co2;
     let h2s = req.h2s;
+    let z_method = req.z_method;
+
+    let fractions = pressure::check_fractions(n2, co2, h2s);
 
     let result = tokio::task::spawn_blocking(move || {
         pwbs.into_par_iter()
-            .map(|pwbs| {
-                let ph = pressure::ph(rg, pc, tc, h, tts, tws, pwbs);
-                let z = pressure::z(pc, tc, tts, ph);
-                let p_over_z = if z != 0.0 { ph / z } else { 0.0 };
+            .map(|pwbs| -> Result<_, CalcError> {
+                fractions?;
+                pressure::check_pressure(pwbs)?;
+                let sol = pressure::check_converged(pressure::ph(
+                    z_method, rg, pc, tc, h, tts, tws, pwbs,
+                ))?;
+                let ph = pressure::check_pressure(sol.value)?;
+                let z = pressure::z_checked(z_method, pc, tc, tts, ph)?.value;
+                let p_over_z = ph / z;
                 let bg = 0.0003447 * z * tts / ph;
                 let density = 3.4844 * ph * rg / (z * tts);
                 let niandu = calculate_viscosity(rg, tts, density, n2, co2, h2s);
-                let cg = pressure::cg(pc, tc, tts, ph);
+                let cg = pressure::cg(z_method, pc, tc, tts, ph).value;
 
-                BatchPhResp {
+                Ok(BatchPhResp {
                     ph,
                     z,
                     p_over_z,
                     bg,
                     niandu,
                     cg,
+                    z_method: z_method.correlation().name(),
+                    pc,
+                    tc,
+                    iterations: sol.iterations,
+                    residual: sol.residual,
+                    converged: sol.converged,
+                })
+            })
+            .map(BatchRow::from)
+            .collect()
+    })
+    .await
+    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    Ok(Json(result))
+}
+
+// ============ 拟压力与拟时间 ============
+pub async fn calculate_pseudo_pressure_handler(
+    Json(req): Json<CalculatePseudoPressureReq>,
+) -> Result<Json<PseudoPressureResp>, StatusCode> {
+    let (pc, tc) = resolve_pc_tc(
+        req.pc,
+        req.tc,
+        Some(req.rg),
+        req.n2,
+        req.co2,
+        req.h2s,
+        req.pc_method,
+        req.sour_correction,
+    )?;
+    pressure::check_fractions(req.n2, req.co2, req.h2s).map_err(|_| StatusCode::BAD_REQUEST)?;
+    let gas = Gas {
+        method: req.z_method,
+        rg: req.rg,
+        pc,
+        tc,
+        t: req.t,
+        yn2: req.n2,
+        yco2: req.co2,
+        yh2s: req.h2s,
+    };
+    let p_ref = req
+        .p_ref
+        .or_else(|| req.pressures.iter().copied().reduce(f64::max))
+        .ok_or(StatusCode::BAD_REQUEST)?;
+    let history: Vec<(f64, f64)> = req.history.iter().map(|h| (h.time, h.p)).collect();
+
+    let (table, times) = tokio::task::spawn_blocking(move || {
+        let table = pseudo_pressure::table(&gas, &req.pressures, p_ref)?;
+        let times = pseudo_pressure::pseudo_time(&gas, &history, p_ref)?;
+        Ok::<_, CalcError>((table, times))
+    })
+    .await
+    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+    .map_err(|_| StatusCode::UNPROCESSABLE_ENTITY)?;
+
+    Ok(Json(PseudoPressureResp {
+        p_ref,
+        table: table
+            .into_iter()
+            .map(|r| PseudoPressureRow {
+                p: r.p,
+                mu: r.mu,
+                z: r.z,
+                mp: r.mp,
+                p_norm: r.p_norm,
+            })
+            .collect(),
+        pseudo_time: times
+            .into_iter()
+            .map(|r| PseudoTimeRow {
+                time: r.time,
+                p: r.p,
+                mu: r.mu,
+                cg: r.cg,
+                ta: r.ta,
+                ta_norm: r.ta_norm,
+            })
+            .collect(),
+    }))
+}
+
+// ============ 产能试井 ============
+pub async fn calculate_deliverability_handler(
+    Json(req): Json<CalculateDeliverabilityReq>,
+) -> Result<Json<DeliverabilityResp>, StatusCode> {
+    let (pc, tc) = resolve_pc_tc(
+        req.pc,
+        req.tc,
+        Some(req.rg),
+        req.n2,
+        req.co2,
+        req.h2s,
+        req.pc_method,
+        req.sour_correction,
+    )?;
+    pressure::check_fractions(req.n2, req.co2, req.h2s).map_err(|_| StatusCode::BAD_REQUEST)?;
+    let gas = Gas {
+        method: req.z_method,
+        rg: req.rg,
+        pc,
+        tc,
+        t: req.t,
+        yn2: req.n2,
+        yco2: req.co2,
+        yh2s: req.h2s,
+    };
+    let (test, form, pr) = (req.test, req.form, req.pr);
+
+    let result = tokio::task::spawn_blocking(move || {
+        deliverability::analyze(test, form, &gas, pr, &req.points)
+    })
+    .await
+    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+    .map_err(|_| StatusCode::UNPROCESSABLE_ENTITY)?;
+
+    Ok(Json(DeliverabilityResp {
+        aof_cn: result.aof_cn,
+        aof_lit: result.aof_lit,
+        cn: result.cn,
+        lit: result.lit,
+        pr,
+        rows: result.rows,
+        ipr: result.ipr,
+        test: test.name(),
+        form: form.name(),
+        z_method: req.z_method.correlation().name(),
+        pc,
+        tc,
+    }))
+}
+
+// ============ 组分分析 ============
+pub async fn calculate_composition_handler(
+    Json(req): Json<CalculateCompositionReq>,
+) -> Result<Json<CompositionResp>, StatusCode> {
+    let total = req.components.iter().map(|c| c.y).sum();
+    let (mix, c7plus) = build_mixture(&req.components, req.c7plus_mw, req.c7plus_sg)?;
+
+    let n2 = mix.fraction(ComponentId::N2);
+    let co2 = mix.fraction(ComponentId::Co2);
+    let h2s = mix.fraction(ComponentId::H2s);
+    let (pc, tc) = mix.kay();
+    let (pc, tc) = pseudo_critical::sour_correct(req.sour_correction, pc, tc, n2, co2, h2s);
+
+    Ok(Json(CompositionResp {
+        total,
+        mw: mix.mw(),
+        rg: mix.rg(),
+        pc,
+        tc,
+        n2,
+        co2,
+        h2s,
+        c7plus,
+    }))
+}
+
+// ============ 状态方程闪蒸 ============
+pub async fn calculate_eos_flash_handler(
+    Json(req): Json<CalculateEosFlashReq>,
+) -> Result<Json<Vec<EosFlashResp>>, StatusCode> {
+    let (mix, _) = build_mixture(&req.components, req.c7plus_mw, req.c7plus_sg)?;
+    let eos = Eos::new(req.eos, &mix, req.kij).ok_or(StatusCode::BAD_REQUEST)?;
+    let t = req.t;
+    let pressures = req.pressures;
+
+    let result = tokio::task::spawn_blocking(move || {
+        pressures
+            .into_par_iter()
+            .map(|p| {
+                let flash = eos.flash(t, p, &mix.y);
+                let vapor = flash.z_vapor.is_some();
+                let liquid = flash.z_liquid.is_some();
+
+                EosFlashResp {
+                    p,
+                    stable: flash.stable,
+                    vapor_fraction: flash.vapor_fraction,
+                    z_v: flash.z_vapor,
+                    z_l: flash.z_liquid,
+                    density_v: flash.z_vapor.map(|z| eos.density(t, p, &flash.y, z)),
+                    density_l: flash.z_liquid.map(|z| eos.density(t, p, &flash.x, z)),
+                    fugacity_v: vapor.then(|| eos.fugacity(t, p, &flash.y, Phase::Vapor)),
+                    fugacity_l: liquid.then(|| eos.fugacity(t, p, &flash.x, Phase::Liquid)),
+                    y: flash.y,
+                    x: flash.x,
+                    iterations: flash.iterations,
+                    residual: flash.residual,
+                    converged: flash.converged,
                 }
             })
             .collect()
@@ -249,7 +1534,114 @@ pub async fn calculate_batch_ph_handler(
     Ok(Json(result))
 }
 
+// ============ AGA8 计算 ============
+pub async fn calculate_z_aga8_handler(
+    Json(req): Json<CalculateZAga8Req>,
+) -> Result<Json<Vec<Aga8Resp>>, StatusCode> {
+    let gas = build_aga8(
+        req.method,
+        req.components.as_deref(),
+        req.rg,
+        req.n2,
+        req.co2,
+    )?;
+    check_pressures(&req.pressures)?;
+    let t = req.t;
+    if t <= 0.0 {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let result = req
+        .pressures
+        .par_iter()
+        .map(|&p| {
+            let sol = gas.z_solve(t, p);
+            Aga8Resp {
+                p,
+                z: sol.value,
+                density: gas.density(t, p),
+                cg: gas.cg(t, p),
+                method: gas.name(),
+                iterations: sol.iterations,
+                residual: sol.residual,
+                converged: sol.converged,
+            }
+        })
+        .collect();
+
+    Ok(Json(result))
+}
+
 // ============ 工具函数 ============
+/// 由请求中的组分分析建立混合物，含 C7+ 时按分子量和相对密度表征
+fn build_mixture(
+    components: &[ComponentFraction],
+    c7plus_mw: Option<f64>,
+    c7plus_sg: Option<f64>,
+) -> Result<(Mixture, Option<PlusFraction>), StatusCode> {
+    let analysis: Vec<(ComponentId, f64)> = components.iter().map(|c| (c.name, c.y)).collect();
+    let c7plus = match (c7plus_mw, c7plus_sg) {
+        (Some(mw), Some(sg)) => Some(composition::characterize_plus(mw, sg)),
+        _ => None,
+    };
+    let mix = Mixture::new(&analysis, c7plus).ok_or(StatusCode::BAD_REQUEST)?;
+    Ok((mix, c7plus))
+}
+
+/// 建立 AGA8 计算对象：详细特征法需组分分析（不支持 C7+），物性值法需相对密度
+fn build_aga8(
+    method: Aga8Method,
+    components: Option<&[ComponentFraction]>,
+    rg: Option<f64>,
+    yn2: f64,
+    yco2: f64,
+) -> Result<Aga8, StatusCode> {
+    match method {
+        Aga8Method::Detail => {
+            let components = components.ok_or(StatusCode::BAD_REQUEST)?;
+            let (mix, _) = build_mixture(components, None, None)?;
+            let gas = Detail::from_mixture(&mix).ok_or(StatusCode::BAD_REQUEST)?;
+            Ok(Aga8::Detail(Box::new(gas)))
+        }
+        Aga8Method::Gross => {
+            let rg = rg.ok_or(StatusCode::BAD_REQUEST)?;
+            Ok(Aga8::Gross(Gross::new(rg, yn2, yco2)))
+        }
+    }
+}
+
+/// 校验单个计算接口的各压力均大于 0，否则返回 400
+fn check_pressures(pressures: &[f64]) -> Result<(), StatusCode> {
+    for &p in pressures {
+        pressure::check_pressure(p).map_err(|_| StatusCode::BAD_REQUEST)?;
+    }
+    Ok(())
+}
+
+/// 解析拟临界参数：请求未给出 pc/tc 时，由相对密度和非烃组分计算；
+/// 随后按请求进行酸性气体校正
+#[allow(clippy::too_many_arguments)]
+fn resolve_pc_tc(
+    pc: Option<f64>,
+    tc: Option<f64>,
+    rg: Option<f64>,
+    yn2: f64,
+    yco2: f64,
+    yh2s: f64,
+    method: PcMethod,
+    sour: SourCorrection,
+) -> Result<(f64, f64), StatusCode> {
+    let (pc, tc) = match (pc, tc) {
+        (Some(pc), Some(tc)) => (pc, tc),
+        _ => {
+            let rg = rg.ok_or(StatusCode::BAD_REQUEST)?;
+            let (ppc, tpc) = pseudo_critical::pseudo_critical(method, rg, yn2, yco2, yh2s);
+            (pc.unwrap_or(ppc), tc.unwrap_or(tpc))
+        }
+    };
+    Ok(pseudo_critical::sour_correct(sour, pc, tc, yn2, yco2, yh2s))
+}
+
 /// 粘度计算 - 消除代码重复
 pub fn calculate_viscosity(rg: f64, t: f64, density: f64, yn2: f64, yco2: f64, yh2s: f64) -> f64 {
     let kn2 = yn2 * (0.00005 * rg + 0.000047) * 100.0;
@@ -0,0 +1,83 @@
+use serde::{Deserialize, Serialize};
+use std::f64::consts::PI;
+
+// 地面标准状况（20 ℃、0.101325 MPa）
+const T_SC: f64 = 293.15;
+const P_SC: f64 = 0.101325;
+
+/// 临界携液模型
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum LoadingModel {
+    /// Turner（1969），球形液滴，含 20% 安全系数（默认）
+    #[default]
+    Turner,
+    /// Coleman（1991），低压气井，不含安全系数
+    Coleman,
+    /// 李闽（2001），椭球形液滴
+    LiMin,
+}
+
+impl LoadingModel {
+    pub fn name(self) -> &'static str {
+        match self {
+            LoadingModel::Turner => "Turner",
+            LoadingModel::Coleman => "Coleman",
+            LoadingModel::LiMin => "李闽",
+        }
+    }
+
+    // vc = K·[σ(ρl - ρg)/ρg²]^0.25 中的系数 K（SI 单位）
+    fn coefficient(self) -> f64 {
+        match self {
+            LoadingModel::Turner => 6.6,
+            LoadingModel::Coleman => 5.5,
+            LoadingModel::LiMin => 2.5,
+        }
+    }
+
+    /// 临界携液流速，m/s
+    /// rho_l、rho_g 为液相、气相密度，kg/m³；sigma 为气液表面张力，N/m
+    pub fn critical_velocity(self, rho_l: f64, rho_g: f64, sigma: f64) -> f64 {
+        self.coefficient() * (sigma * (rho_l - rho_g) / rho_g.powi(2)).powf(0.25)
+    }
+}
+
+/// 井筒积液的液相
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Liquid {
+    #[default]
+    Water,
+    Condensate,
+}
+
+impl Liquid {
+    /// 密度，kg/m³
+    pub fn density(self) -> f64 {
+        match self {
+            Liquid::Water => 1074.0,
+            Liquid::Condensate => 721.0,
+        }
+    }
+
+    /// 气液表面张力，N/m
+    pub fn surface_tension(self) -> f64 {
+        match self {
+            Liquid::Water => 0.06,
+            Liquid::Condensate => 0.02,
+        }
+    }
+}
+
+/// 由井下流速换算地面标准状况下的气量，m³/d
+/// q = 86400·A·v·(p/psc)·(Tsc/T)/Z，A = πd²/4
+/// d 油管内径 m，p MPa，t K
+pub fn rate_from_velocity(d: f64, p: f64, t: f64, z: f64, v: f64) -> f64 {
+    86400.0 * PI * d * d / 4.0 * v * p * T_SC / (P_SC * t * z)
+}
+
+/// 地面标准状况下的气量（m³/d）对应的井下流速，m/s
+pub fn velocity_from_rate(d: f64, p: f64, t: f64, z: f64, qg: f64) -> f64 {
+    qg / rate_from_velocity(d, p, t, z, 1.0)
+}
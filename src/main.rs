@@ -6,10 +6,29 @@ use axum::{
 use dotenv::dotenv;
 use tower_http::services::ServeDir;
 
+mod aga8;
+mod aquifer;
+mod choke;
+mod composition;
+mod datum;
 mod db;
+mod deliverability;
+mod eos;
+mod erosion;
+mod friction;
 mod handlers;
+mod liquid_loading;
+mod material_balance;
 mod models;
+mod multiphase;
 mod pressure;
+mod pseudo_critical;
+mod pseudo_pressure;
+mod regression;
+mod solver;
+mod temperature;
+mod trajectory;
+mod zfactor;
 
 // ============ 应用启动 ============
 #[tokio::main]
@@ -37,6 +56,90 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             post(handlers::calculate_niandu_handler),
         )
         .route("/api/calculatePwbs", post(handlers::calculate_pwbs_handler))
+        // 拟压力接口
+        .route(
+            "/api/calculatePseudoPressure",
+            post(handlers::calculate_pseudo_pressure_handler),
+        )
+        // 组分分析接口
+        .route(
+            "/api/calculateComposition",
+            post(handlers::calculate_composition_handler),
+        )
+        // 状态方程闪蒸接口
+        .route(
+            "/api/calculateEosFlash",
+            post(handlers::calculate_eos_flash_handler),
+        )
+        // 井眼轨迹接口
+        .route("/api/getTrajectory", post(handlers::get_trajectory_handler))
+        .route(
+            "/api/saveTrajectory",
+            post(handlers::save_trajectory_handler),
+        )
+        // 积液分析接口
+        .route(
+            "/api/calculateLiquidLoading",
+            post(handlers::calculate_liquid_loading_handler),
+        )
+        // 气液两相流接口
+        .route(
+            "/api/calculateMultiphase",
+            post(handlers::calculate_multiphase_handler),
+        )
+        // 油管尺寸优选接口
+        .route(
+            "/api/calculateTubingStudy",
+            post(handlers::calculate_tubing_study_handler),
+        )
+        // 冲蚀流速校核接口
+        .route(
+            "/api/calculateErosion",
+            post(handlers::calculate_erosion_handler),
+        )
+        // 井筒压力剖面接口
+        .route(
+            "/api/calculateTraverse",
+            post(handlers::calculate_traverse_handler),
+        )
+        // 物质平衡接口
+        .route(
+            "/api/calculateMaterialBalance",
+            post(handlers::calculate_material_balance_handler),
+        )
+        .route(
+            "/api/calculateWaterDrive",
+            post(handlers::calculate_water_drive_handler),
+        )
+        // 产能试井接口
+        .route(
+            "/api/calculateDeliverability",
+            post(handlers::calculate_deliverability_handler),
+        )
+        // 压力折算到基准面接口
+        .route(
+            "/api/calculateDatumPressure",
+            post(handlers::calculate_datum_handler),
+        )
+        // 摩阻系数接口
+        .route(
+            "/api/calculateFriction",
+            post(handlers::calculate_friction_handler),
+        )
+        // 油嘴流动接口
+        .route(
+            "/api/calculateChokeRate",
+            post(handlers::calculate_choke_rate_handler),
+        )
+        .route(
+            "/api/calculateChokeSize",
+            post(handlers::calculate_choke_size_handler),
+        )
+        // AGA8 偏差因子接口
+        .route(
+            "/api/calculateZAga8",
+            post(handlers::calculate_z_aga8_handler),
+        )
         // 批量计算接口
         .route(
             "/api/calculateBatchPVT",
@@ -46,6 +149,10 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             "/api/calculateBatchPb",
             post(handlers::calculate_batch_pb_handler),
         )
+        .route(
+            "/api/calculateBatchPwf",
+            post(handlers::calculate_batch_pwf_handler),
+        )
         .route(
             "/api/calculateBatchPh",
             post(handlers::calculate_batch_ph_handler),
@@ -0,0 +1,541 @@
+use crate::aquifer::{self, Aquifer, AquiferGeometry, AquiferModel};
+use crate::pressure::{self, CalcError};
+use crate::regression::{self, LinearFit};
+use crate::zfactor::ZMethod;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+// 拟合直线的输出点数
+const LINE_POINTS: usize = 21;
+
+/// 压力-累产历史中的一点
+#[derive(Deserialize, Debug, Clone, Copy)]
+pub struct MbPoint {
+    pub p: f64,  // 地层压力，MPa
+    pub gp: f64, // 累计产气量，10⁸m³
+}
+
+/// 计算得到的 p/Z 点
+#[derive(Serialize, Debug, Clone, Copy)]
+pub struct PzPoint {
+    pub p: f64,
+    pub gp: f64,
+    pub z: f64,
+    pub p_over_z: f64,
+    pub corrected: f64, // 考虑岩石和束缚水弹性膨胀后的 p/Z·[1 - ce·(pi - p)]
+    pub fitted: f64,    // 拟合直线上同一累产下的 p/Z
+}
+
+/// 绘图用的直线点
+#[derive(Serialize, Debug, Clone, Copy)]
+pub struct LinePoint {
+    pub gp: f64,
+    pub p_over_z: f64,
+}
+
+/// 物质平衡方法
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum MbMethod {
+    /// p/Z 压降法（定容气藏，默认）
+    #[default]
+    POverZ,
+    /// Ramagost-Farshad（1981），按 cf、cw、Swi 校正 p/Z
+    RamagostFarshad,
+    /// Roach（1981），回归同时求地质储量和有效压缩系数
+    Roach,
+    /// Fetkovich（1998），计入连通水体及非储层孔隙体积比 M
+    Fetkovich,
+}
+
+impl MbMethod {
+    pub fn name(self) -> &'static str {
+        match self {
+            MbMethod::POverZ => "p/Z 压降法",
+            MbMethod::RamagostFarshad => "Ramagost-Farshad",
+            MbMethod::Roach => "Roach",
+            MbMethod::Fetkovich => "Fetkovich",
+        }
+    }
+}
+
+/// 异常高压气藏的岩石与地层水压缩性参数
+#[derive(Deserialize, Debug, Clone, Copy, Default)]
+#[serde(default)]
+pub struct Compressibility {
+    pub cf: f64,  // 岩石（孔隙）压缩系数，1/MPa
+    pub cw: f64,  // 地层水压缩系数，1/MPa
+    pub swi: f64, // 束缚水饱和度
+    pub m: f64,   // 连通水体及非储层孔隙体积与气藏孔隙体积之比（Fetkovich）
+}
+
+impl Compressibility {
+    /// 有效压缩系数 ce，1/MPa
+    /// Ramagost-Farshad：ce = (cf + cw·Swi)/(1 - Swi)
+    /// Fetkovich：ce = [Swi·cw + cf + M·(cw + cf)]/(1 - Swi)
+    fn ce(&self, method: MbMethod) -> f64 {
+        let m = match method {
+            MbMethod::Fetkovich => self.m,
+            _ => 0.0,
+        };
+        (self.swi * self.cw + self.cf + m * (self.cw + self.cf)) / (1.0 - self.swi)
+    }
+}
+
+/// 物质平衡分析结果
+#[derive(Debug, Clone)]
+pub struct PzAnalysis {
+    pub points: Vec<PzPoint>,
+    pub fit: LinearFit,       // 回归统计（Roach 法为 Roach 坐标下的直线）
+    pub ogip: f64,            // 原始地质储量，10⁸m³
+    pub p_over_z_i: f64,      // 原始 p/Z，MPa
+    pub ce: f64,              // 有效压缩系数，1/MPa
+    pub line: Vec<LinePoint>, // 累产 0～OGIP 的拟合直线（校正后的 p/Z）
+}
+
+impl PzAnalysis {
+    /// 废弃压力下的累计产气量（10⁸m³）与采收率
+    /// 参数:
+    ///   pi         – 原始地层压力，MPa
+    ///   pa         – 废弃压力，MPa
+    ///   p_over_z_a – 废弃压力下的 p/Z，MPa
+    pub fn recovery(&self, pi: f64, pa: f64, p_over_z_a: f64) -> (f64, f64) {
+        let corrected = p_over_z_a * (1.0 - self.ce * (pi - pa));
+        let rf = (1.0 - corrected / self.p_over_z_i).clamp(0.0, 1.0);
+        (self.ogip * rf, rf)
+    }
+}
+
+/// 按地层温度计算各点 p/Z
+pub fn p_over_z(
+    method: ZMethod,
+    pc: f64,
+    tc: f64,
+    t: f64,
+    history: &[MbPoint],
+) -> Result<Vec<PzPoint>, CalcError> {
+    history
+        .iter()
+        .map(|h| {
+            if h.gp < 0.0 {
+                return Err(CalcError::InvalidInput("累计产气量不能为负"));
+            }
+            let z = pressure::z_checked(method, pc, tc, t, h.p)?.value;
+            Ok(PzPoint {
+                p: h.p,
+                gp: h.gp,
+                z,
+                p_over_z: h.p / z,
+                corrected: h.p / z,
+                fitted: 0.0,
+            })
+        })
+        .collect()
+}
+
+/// 物质平衡回归，外推校正后的 p/Z = 0 得原始地质储量
+/// p/Z·[1 - ce·(pi - p)] = (p/Z)i·(1 - Gp/G)
+/// 参数:
+///   method  – 物质平衡方法
+///   rock    – 压缩性参数（Ramagost-Farshad、Fetkovich）
+///   initial – 原始地层压力下的 p/Z 点（Roach 法使用）
+///   points  – 压力-累产历史的 p/Z 点
+pub fn analyze(
+    method: MbMethod,
+    rock: &Compressibility,
+    initial: &PzPoint,
+    mut points: Vec<PzPoint>,
+) -> Result<PzAnalysis, CalcError> {
+    if !(0.0..1.0).contains(&rock.swi) || rock.cf < 0.0 || rock.cw < 0.0 || rock.m < 0.0 {
+        return Err(CalcError::InvalidInput(
+            "束缚水饱和度须在 0～1 之间，压缩系数与体积比不能为负",
+        ));
+    }
+    let pi = initial.p;
+
+    let (fit, ogip, p_over_z_i, ce) = match method {
+        MbMethod::Roach => {
+            // [(p/Z)i/(p/Z) - 1]/Δp = (1/G)·Gp·[(p/Z)i/(p/Z)]/Δp - ce
+            let (x, y): (Vec<f64>, Vec<f64>) = points
+                .iter()
+                .filter(|p| pi - p.p > 0.0)
+                .map(|p| {
+                    let (dp, ratio) = (pi - p.p, initial.p_over_z / p.p_over_z);
+                    (p.gp * ratio / dp, (ratio - 1.0) / dp)
+                })
+                .unzip();
+            let fit = regression::linear_fit(&x, &y)?;
+            if fit.slope <= 0.0 {
+                return Err(CalcError::InvalidInput(
+                    "Roach 图斜率不为正，无法求地质储量",
+                ));
+            }
+            (fit, 1.0 / fit.slope, initial.p_over_z, -fit.intercept)
+        }
+        _ => {
+            let ce = match method {
+                MbMethod::POverZ => 0.0,
+                _ => rock.ce(method),
+            };
+            let x: Vec<f64> = points.iter().map(|p| p.gp).collect();
+            let y: Vec<f64> = points
+                .iter()
+                .map(|p| p.p_over_z * (1.0 - ce * (pi - p.p)))
+                .collect();
+            let fit = regression::linear_fit(&x, &y)?;
+            if fit.slope >= 0.0 || fit.intercept <= 0.0 {
+                return Err(CalcError::InvalidInput(
+                    "p/Z 未随累计产气量下降，无法外推地质储量",
+                ));
+            }
+            (fit, fit.x_intercept(), fit.intercept, ce)
+        }
+    };
+
+    let straight = |gp: f64| p_over_z_i * (1.0 - gp / ogip);
+    for p in points.iter_mut() {
+        p.corrected = p.p_over_z * (1.0 - ce * (pi - p.p));
+        p.fitted = straight(p.gp);
+    }
+    Ok(PzAnalysis {
+        points,
+        fit,
+        ogip,
+        p_over_z_i,
+        ce,
+        line: line(ogip, straight),
+    })
+}
+
+// 累产 0～ogip 等分取点
+fn line(ogip: f64, f: impl Fn(f64) -> f64) -> Vec<LinePoint> {
+    (0..LINE_POINTS)
+        .map(|k| {
+            let gp = ogip * k as f64 / (LINE_POINTS - 1) as f64;
+            LinePoint {
+                gp,
+                p_over_z: f(gp),
+            }
+        })
+        .collect()
+}
+
+// ============ 水驱气藏物质平衡（Havlena-Odeh） ============
+// 水侵参数搜索：对数网格点数与细化轮数
+const SEARCH_POINTS: usize = 25;
+const SEARCH_REFINE: usize = 4;
+// 未给出径向水体 rD 时的候选值（None 为无限大）
+const RD_CANDIDATES: [Option<f64>; 13] = [
+    None,
+    Some(1.5),
+    Some(2.0),
+    Some(3.0),
+    Some(4.0),
+    Some(5.0),
+    Some(6.0),
+    Some(8.0),
+    Some(10.0),
+    Some(15.0),
+    Some(20.0),
+    Some(30.0),
+    Some(50.0),
+];
+
+/// 水驱气藏生产动态中的一点
+#[derive(Deserialize, Debug, Clone, Copy)]
+pub struct WaterDrivePoint {
+    pub t: f64,  // 生产时间，d
+    pub p: f64,  // 地层压力，MPa
+    pub gp: f64, // 累计产气量，10⁸m³
+    #[serde(default)]
+    pub wp: f64, // 累计产水量，10⁴m³
+}
+
+/// Havlena-Odeh 分析表中的一行
+#[derive(Serialize, Debug, Clone, Copy)]
+pub struct WaterDriveRow {
+    pub t: f64,
+    pub p: f64,
+    pub gp: f64,
+    pub wp: f64,
+    pub bg: f64,         // 气体体积系数，m³/m³
+    pub f: f64,          // 地下采出量 F = Gp·Bg + Wp·Bw，10⁴m³
+    pub et: f64,         // 气体及岩石、束缚水膨胀量 Et，m³/m³
+    pub we: f64,         // 累计水侵量，10⁴m³
+    pub f_over_et: f64,  // F/Et，10⁸m³
+    pub we_over_et: f64, // We/Et，10⁸m³
+    pub fitted: f64,     // 拟合的地下采出量 G·Et + We，10⁴m³
+}
+
+/// 拟合得到的水体参数（仅返回所用模型的参数）
+#[derive(Serialize, Debug, Clone, Copy, Default)]
+pub struct AquiferParams {
+    pub k: Option<f64>,       // Schilthuis 水侵系数，m³/(d·MPa)
+    pub j: Option<f64>,       // Fetkovich 水体采出指数，m³/(d·MPa)
+    pub wei: Option<f64>,     // Fetkovich 最大可侵入水量，10⁴m³
+    pub u: Option<f64>,       // 非稳态水侵常数，m³/MPa
+    pub td_coef: Option<f64>, // 无因次时间系数 tD/t，1/d
+    pub rd: Option<f64>,      // 径向水体外半径与气藏半径之比，无限大时为空
+}
+
+/// 水驱气藏物质平衡分析结果
+#[derive(Debug, Clone)]
+pub struct WaterDriveAnalysis {
+    pub rows: Vec<WaterDriveRow>,
+    pub ogip: f64,            // 原始地质储量，10⁸m³
+    pub volumetric_ogip: f64, // 不计水侵时拟合的储量，10⁸m³
+    pub params: AquiferParams,
+    pub r2: f64, // 地下采出量拟合的决定系数
+}
+
+/// 水驱气藏的输入条件
+#[derive(Debug, Clone, Copy)]
+pub struct WaterDrive {
+    pub model: AquiferModel,
+    pub aquifer: AquiferGeometry,
+    pub rd: Option<f64>,      // 径向水体 rD，缺省时在候选值中优选
+    pub td_coef: Option<f64>, // 无因次时间系数，1/d，缺省时搜索
+    pub pi: f64,              // 原始地层压力，MPa
+    pub bgi: f64,             // 原始气体体积系数
+    pub bw: f64,              // 地层水体积系数
+}
+
+/// Havlena-Odeh 水驱气藏物质平衡：F = G·Et + We
+/// Et = Bg - Bgi + Bgi·ce·(pi - p)，ce 按 Ramagost-Farshad 由 cf、cw、Swi 计算；
+/// 水侵模型中的线性常数（k、U）由最小二乘求得，其余参数在对数网格上搜索使残差平方和最小
+/// 参数:
+///   drive   – 水侵模型及原始条件
+///   rock    – 压缩性参数
+///   history – 生产动态（时间递增）
+///   bg      – 各点气体体积系数
+pub fn water_drive(
+    drive: &WaterDrive,
+    rock: &Compressibility,
+    history: &[WaterDrivePoint],
+    bg: &[f64],
+) -> Result<WaterDriveAnalysis, CalcError> {
+    if !(0.0..1.0).contains(&rock.swi) || rock.cf < 0.0 || rock.cw < 0.0 {
+        return Err(CalcError::InvalidInput(
+            "束缚水饱和度须在 0～1 之间，压缩系数不能为负",
+        ));
+    }
+    if history.len() < 2 {
+        return Err(CalcError::InvalidInput("物质平衡至少需要两个数据点"));
+    }
+    if history.windows(2).any(|w| w[1].t < w[0].t) || history[0].t < 0.0 {
+        return Err(CalcError::InvalidInput("生产时间须递增且不能为负"));
+    }
+    if history.iter().any(|h| h.gp < 0.0 || h.wp < 0.0) {
+        return Err(CalcError::InvalidInput("累计产气量、产水量不能为负"));
+    }
+    if drive.rd.is_some_and(|rd| rd <= 1.0) || drive.td_coef.is_some_and(|c| c <= 0.0) {
+        return Err(CalcError::InvalidInput(
+            "水体半径比须大于 1，无因次时间系数须大于 0",
+        ));
+    }
+
+    let ce = rock.ce(MbMethod::RamagostFarshad);
+    let (pi, bgi) = (drive.pi, drive.bgi);
+    let t: Vec<f64> = history.iter().map(|h| h.t).collect();
+    let p: Vec<f64> = history.iter().map(|h| h.p).collect();
+    // 地下采出量（m³）与膨胀量
+    let f: Vec<f64> = history
+        .iter()
+        .zip(bg)
+        .map(|(h, bg)| h.gp * 1e8 * bg + h.wp * 1e4 * drive.bw)
+        .collect();
+    let et: Vec<f64> = history
+        .iter()
+        .zip(bg)
+        .map(|(h, bg)| bg - bgi + bgi * ce * (pi - h.p))
+        .collect();
+    let volumetric = least_squares(&f, &et, None);
+
+    let fit = match drive.model {
+        AquiferModel::Schilthuis => {
+            let unit = aquifer::schilthuis(&t, &p, pi, 1.0);
+            let (g, c, sse) = least_squares(&f, &et, Some(&unit));
+            let params = AquiferParams {
+                k: Some(c),
+                ..Default::default()
+            };
+            (g, scale(&unit, c), params, sse)
+        }
+        AquiferModel::Fetkovich => {
+            // 以最大地下采出量为尺度搜索 Wei，以 λ = J·pi/Wei 为时间常数
+            let f_max = f.iter().fold(0.0_f64, |a, &b| a.max(b)).max(1.0);
+            let sse_of = |lambda: f64, wei: f64| {
+                let we = aquifer::fetkovich(&t, &p, pi, lambda * wei / pi, wei);
+                let residual: Vec<f64> = f.iter().zip(&we).map(|(f, we)| f - we).collect();
+                least_squares(&residual, &et, None).2
+            };
+            let (lambda, _) = search_log(1e-6, 1.0, |lambda| {
+                search_log(0.1 * f_max, 1e4 * f_max, |wei| sse_of(lambda, wei)).1
+            });
+            let (wei, _) = search_log(0.1 * f_max, 1e4 * f_max, |wei| sse_of(lambda, wei));
+            let j = lambda * wei / pi;
+            let we = aquifer::fetkovich(&t, &p, pi, j, wei);
+            let residual: Vec<f64> = f.iter().zip(&we).map(|(f, we)| f - we).collect();
+            let (g, _, sse) = least_squares(&residual, &et, None);
+            let params = AquiferParams {
+                j: Some(j),
+                wei: Some(wei / 1e4),
+                ..Default::default()
+            };
+            (g, we, params, sse)
+        }
+        AquiferModel::VanEverdingenHurst | AquiferModel::CarterTracy => {
+            let candidates: Vec<Aquifer> = match (drive.aquifer, drive.rd) {
+                (AquiferGeometry::Linear, _) => vec![Aquifer::Linear],
+                (AquiferGeometry::Radial, Some(rd)) => vec![Aquifer::Radial(Some(rd))],
+                (AquiferGeometry::Radial, None) => RD_CANDIDATES
+                    .iter()
+                    .map(|&rd| Aquifer::Radial(rd))
+                    .collect(),
+            };
+            let unit = |aq: &Aquifer, td_coef: f64| match drive.model {
+                AquiferModel::CarterTracy => aquifer::carter_tracy(aq, &t, &p, pi, 1.0, td_coef),
+                _ => aquifer::van_everdingen_hurst(aq, &t, &p, pi, 1.0, td_coef),
+            };
+            let sse_of =
+                |aq: &Aquifer, td_coef: f64| least_squares(&f, &et, Some(&unit(aq, td_coef))).2;
+            let (aq, td_coef, _) = candidates
+                .par_iter()
+                .map(|aq| {
+                    let (td_coef, sse) = match drive.td_coef {
+                        Some(c) => (c, sse_of(aq, c)),
+                        None => search_log(1e-5, 10.0, |c| sse_of(aq, c)),
+                    };
+                    (*aq, td_coef, sse)
+                })
+                .min_by(|a, b| a.2.total_cmp(&b.2))
+                .ok_or(CalcError::InvalidInput("无可用的水体参数"))?;
+            let w = unit(&aq, td_coef);
+            let (g, u, sse) = least_squares(&f, &et, Some(&w));
+            let params = AquiferParams {
+                u: Some(u),
+                td_coef: Some(td_coef),
+                rd: match aq {
+                    Aquifer::Radial(rd) => rd,
+                    Aquifer::Linear => None,
+                },
+                ..Default::default()
+            };
+            (g, scale(&w, u), params, sse)
+        }
+    };
+    let (g, we, params, sse) = fit;
+    if !(g.is_finite() && g > 0.0) {
+        return Err(CalcError::InvalidInput(
+            "地下采出量与膨胀量不匹配，无法求地质储量",
+        ));
+    }
+
+    let mean = f.iter().sum::<f64>() / f.len() as f64;
+    let sst: f64 = f.iter().map(|f| (f - mean).powi(2)).sum();
+    let rows = history
+        .iter()
+        .enumerate()
+        .map(|(n, h)| WaterDriveRow {
+            t: h.t,
+            p: h.p,
+            gp: h.gp,
+            wp: h.wp,
+            bg: bg[n],
+            f: f[n] / 1e4,
+            et: et[n],
+            we: we[n] / 1e4,
+            f_over_et: f[n] / et[n] / 1e8,
+            we_over_et: we[n] / et[n] / 1e8,
+            fitted: (g * et[n] + we[n]) / 1e4,
+        })
+        .collect();
+    Ok(WaterDriveAnalysis {
+        rows,
+        ogip: g / 1e8,
+        volumetric_ogip: volumetric.0 / 1e8,
+        params,
+        r2: if sst > 0.0 { 1.0 - sse / sst } else { 1.0 },
+    })
+}
+
+fn scale(v: &[f64], c: f64) -> Vec<f64> {
+    v.iter().map(|x| x * c).collect()
+}
+
+// 无截距最小二乘 F = G·Et（+ C·W），C 不为负；返回 (G, C, 残差平方和)
+fn least_squares(f: &[f64], et: &[f64], w: Option<&[f64]>) -> (f64, f64, f64) {
+    let dot = |a: &[f64], b: &[f64]| a.iter().zip(b).map(|(x, y)| x * y).sum::<f64>();
+    let only_g = || {
+        let g = dot(f, et) / dot(et, et);
+        (g, 0.0)
+    };
+    let (g, c) = match w {
+        Some(w) => {
+            let (a11, a12, a22) = (dot(et, et), dot(et, w), dot(w, w));
+            let (b1, b2) = (dot(f, et), dot(f, w));
+            let det = a11 * a22 - a12 * a12;
+            let c = (a11 * b2 - a12 * b1) / det;
+            if det.abs() > 0.0 && c >= 0.0 {
+                ((b1 * a22 - a12 * b2) / det, c)
+            } else {
+                only_g()
+            }
+        }
+        None => only_g(),
+    };
+    let sse = f
+        .iter()
+        .enumerate()
+        .map(|(n, f)| {
+            let we = w.map_or(0.0, |w| c * w[n]);
+            (f - g * et[n] - we).powi(2)
+        })
+        .sum();
+    (g, c, sse)
+}
+
+// 在 [lo, hi] 的对数网格上求 f 的最小值，并在最优点附近逐轮加密
+fn search_log(lo: f64, hi: f64, f: impl Fn(f64) -> f64) -> (f64, f64) {
+    let (mut a, mut b) = (lo.ln(), hi.ln());
+    let mut best = (lo, f64::INFINITY);
+    for _ in 0..=SEARCH_REFINE {
+        let h = (b - a) / (SEARCH_POINTS - 1) as f64;
+        for k in 0..SEARCH_POINTS {
+            let x = (a + h * k as f64).exp();
+            let fx = f(x);
+            if fx < best.1 {
+                best = (x, fx);
+            }
+        }
+        // 以历轮最优点为中心缩小区间
+        let center = best.0.ln();
+        (a, b) = (center - h, center + h);
+    }
+    best
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn search_log_finds_minimum_of_convex_function() {
+        // 以 ln x 计为凸函数，最小值在 x = 37
+        let (x, fx) = search_log(1e-3, 1e4, |x: f64| (x / 37.0).ln().powi(2));
+        assert!((x / 37.0 - 1.0).abs() < 1e-3, "x = {x}");
+        assert!(fx < 1e-6);
+    }
+
+    #[test]
+    fn search_log_refines_around_best_point() {
+        // 最小值紧邻首轮网格点：第二轮网格中心点即最优，后续各轮须继续以它为中心加密
+        let h = (1e2f64.ln() - 1e-2f64.ln()) / (SEARCH_POINTS - 1) as f64;
+        let target = (1e-2f64.ln() + 15.0 * h + 0.01 * h).exp();
+        let (x, _) = search_log(1e-2, 1e2, |x: f64| (x.ln() - target.ln()).abs());
+        assert!(
+            (x / target - 1.0).abs() < 1e-4,
+            "x = {x}, target = {target}"
+        );
+    }
+}
@@ -1,3 +1,25 @@
+use crate::aga8::Aga8Method;
+use crate::aquifer::{AquiferGeometry, AquiferModel};
+use crate::choke::ChokeMethod;
+use crate::composition::{ComponentId, PlusFraction};
+use crate::deliverability::{
+    CnCoefficients, DeliverabilityTest, IprPoint, LitCoefficients, PotentialForm, TestPoint,
+    TestRow,
+};
+use crate::eos::EosModel;
+use crate::friction::FrictionMethod;
+use crate::liquid_loading::{Liquid, LoadingModel};
+use crate::material_balance::{
+    AquiferParams, Compressibility, LinePoint, MbMethod, MbPoint, PzPoint, WaterDrivePoint,
+    WaterDriveRow,
+};
+use crate::multiphase::{Liquids, MultiphaseMethod, MultiphasePoint};
+use crate::pressure::{BhpMethod, CalcError, TraversePoint, TubingSection};
+use crate::pseudo_critical::{PcMethod, SourCorrection};
+use crate::regression::LinearFit;
+use crate::temperature::TemperatureModel;
+use crate::trajectory::SurveyStation;
+use crate::zfactor::ZMethod;
 use serde::{Deserialize, Serialize};
 
 // ============ 认证 ============
@@ -11,90 +33,339 @@ pub struct LoginReq {
 #[derive(Deserialize)]
 pub struct WellDataReq {
     pub well_no: String,
+    #[serde(default)]
+    pub pc_method: PcMethod, // 表中未录入拟临界参数时的计算方法
 }
 
 #[derive(Serialize)]
 pub struct WellData {
     pub wellname: String,
     pub md: f64,
+    pub tvd: f64, // 中部垂深，无井斜数据时等于 md
     pub th: f64,
     pub tb: f64,
     pub rg: f64,
-    pub pc: f64,
-    pub tc: f64,
+    pub pc: Option<f64>, // 未录入且无相对密度时为 null
+    pub tc: Option<f64>,
     pub n2: f64,
     pub co2: f64,
     pub h2s: f64,
 }
 
+// ============ 井眼轨迹 ============
+#[derive(Deserialize)]
+pub struct SaveTrajectoryReq {
+    pub well_no: String,
+    pub stations: Vec<SurveyStation>, // 测斜数据，按测深递增
+}
+
 // ============ 单个计算请求 ============
 #[derive(Deserialize, Debug)]
 pub struct CalculateZReq {
     pub pressures: Vec<f64>,
-    pub pc: f64,
-    pub tc: f64,
+    pub pc: Option<f64>, // 缺省时由 rg 计算
+    pub tc: Option<f64>,
     pub t: f64,
+    pub rg: Option<f64>,
+    #[serde(default)]
+    pub n2: f64,
+    #[serde(default)]
+    pub co2: f64,
+    #[serde(default)]
+    pub h2s: f64,
+    #[serde(default)]
+    pub z_method: ZMethod,
+    #[serde(default)]
+    pub pc_method: PcMethod,
+    #[serde(default)]
+    pub sour_correction: SourCorrection,
+}
+
+#[derive(Serialize)]
+pub struct ZResp {
+    pub z: f64,
+    pub iterations: u32, // 迭代次数
+    pub residual: f64,   // 终止时残差
+    pub converged: bool, // 是否收敛
+}
+
+/// 单个计算接口的响应：各压力下的结果及计算所用关联式、拟临界参数
+#[derive(Serialize)]
+pub struct PropertyResp<T> {
+    pub values: Vec<T>,
+    pub z_method: &'static str, // 所用 Z 因子关联式
+    pub pc: f64,                // 计算所用拟临界压力（酸性气体校正后）
+    pub tc: f64,                // 计算所用拟临界温度（酸性气体校正后）
 }
 
 #[derive(Deserialize, Debug)]
 pub struct CalculateBgReq {
     pub pressures: Vec<f64>,
-    pub pc: f64,
-    pub tc: f64,
+    pub pc: Option<f64>, // 缺省时由 rg 计算
+    pub tc: Option<f64>,
     pub t: f64,
+    pub rg: Option<f64>,
+    #[serde(default)]
+    pub n2: f64,
+    #[serde(default)]
+    pub co2: f64,
+    #[serde(default)]
+    pub h2s: f64,
+    #[serde(default)]
+    pub z_method: ZMethod,
+    #[serde(default)]
+    pub pc_method: PcMethod,
+    #[serde(default)]
+    pub sour_correction: SourCorrection,
 }
 
 #[derive(Deserialize, Debug)]
 pub struct CalculateCgReq {
     pub pressures: Vec<f64>,
-    pub pc: f64,
-    pub tc: f64,
+    pub pc: Option<f64>, // 缺省时由 rg 计算
+    pub tc: Option<f64>,
     pub t: f64,
+    pub rg: Option<f64>,
+    #[serde(default)]
+    pub n2: f64,
+    #[serde(default)]
+    pub co2: f64,
+    #[serde(default)]
+    pub h2s: f64,
+    #[serde(default)]
+    pub z_method: ZMethod,
+    #[serde(default)]
+    pub pc_method: PcMethod,
+    #[serde(default)]
+    pub sour_correction: SourCorrection,
 }
 
 #[derive(Deserialize, Debug)]
 pub struct CalculateDensityReq {
     pub pressures: Vec<f64>,
-    pub pc: f64,
-    pub tc: f64,
+    pub pc: Option<f64>, // 缺省时由 rg 计算
+    pub tc: Option<f64>,
     pub t: f64,
     pub rg: f64,
+    #[serde(default)]
+    pub n2: f64,
+    #[serde(default)]
+    pub co2: f64,
+    #[serde(default)]
+    pub h2s: f64,
+    #[serde(default)]
+    pub z_method: ZMethod,
+    #[serde(default)]
+    pub pc_method: PcMethod,
+    #[serde(default)]
+    pub sour_correction: SourCorrection,
 }
 
 #[derive(Deserialize, Debug)]
 pub struct CalculateNianduReq {
     pub pressures: Vec<f64>,
-    pub pc: f64,
-    pub tc: f64,
+    pub pc: Option<f64>, // 缺省时由 rg 计算
+    pub tc: Option<f64>,
     pub t: f64,
     pub rg: f64,
     pub n2: f64,
     pub co2: f64,
     pub h2s: f64,
+    #[serde(default)]
+    pub z_method: ZMethod,
+    #[serde(default)]
+    pub pc_method: PcMethod,
+    #[serde(default)]
+    pub sour_correction: SourCorrection,
 }
 
 #[derive(Deserialize, Debug)]
 pub struct CalculatePwbsReq {
+    pub well_no: Option<String>, // 有井斜数据时按井眼轨迹换算垂深
     pub rg: f64,
+    pub pc: Option<f64>, // 缺省时由 rg 计算
+    pub tc: Option<f64>,
+    pub h: f64, // 中部井深（测深），m
+    pub tts: f64,
+    pub tws: f64,
+    pub pts: f64,
+    #[serde(default)]
+    pub n2: f64,
+    #[serde(default)]
+    pub co2: f64,
+    #[serde(default)]
+    pub h2s: f64,
+    #[serde(default)]
+    pub z_method: ZMethod,
+    #[serde(default)]
+    pub pc_method: PcMethod,
+    #[serde(default)]
+    pub sour_correction: SourCorrection,
+    pub method: Option<BhpMethod>, // 缺省为平均温度平均偏差系数法
+}
+
+#[derive(Serialize)]
+pub struct PwbsResp {
+    pub pwbs: f64,
+    pub method: &'static str, // 所用井底静压计算方法
+    pub iterations: u32,      // 迭代次数
+    pub residual: f64,        // 终止时残差
+    pub converged: bool,      // 是否收敛
+}
+
+// ============ 井筒压力剖面 ============
+#[derive(Deserialize)]
+pub struct CalculateTraverseReq {
+    pub well_no: Option<String>, // 有井斜数据时按井眼轨迹换算垂深
+    pub rg: f64,
+    pub pc: Option<f64>, // 缺省时由 rg 计算
+    pub tc: Option<f64>,
+    pub h: Option<f64>, // 静气柱井深（测深），m（未传入 sections 时使用）
+    #[serde(default)]
+    pub sections: Vec<TubingSection>, // 管柱分段（长度为测深），自井口向下
+    #[serde(default)]
+    pub ee: f64, // 绝对粗糙度，m
+    #[serde(default)]
+    pub friction: FrictionMethod, // 摩阻系数计算方法
+    #[serde(default)]
+    pub qg: f64, // 产气量，m³/d，0 为静气柱
+    pub step: f64,      // 测点间距，m
+    pub tts: f64,
+    pub tws: f64,
+    #[serde(default)]
+    pub temperature: TemperatureModel, // 井筒温度模型，缺省为线性分布
+    pub pts: f64,
+    #[serde(default)]
+    pub n2: f64,
+    #[serde(default)]
+    pub co2: f64,
+    #[serde(default)]
+    pub h2s: f64,
+    #[serde(default)]
+    pub z_method: ZMethod,
+    #[serde(default)]
+    pub pc_method: PcMethod,
+    #[serde(default)]
+    pub sour_correction: SourCorrection,
+}
+
+#[derive(Serialize)]
+pub struct TraverseResp {
+    pub points: Vec<TraversePoint>,
+    pub temperature: &'static str, // 所用井筒温度模型
+    pub z_method: &'static str,
     pub pc: f64,
     pub tc: f64,
-    pub h: f64,
+    pub iterations: u32, // 迭代次数
+    pub residual: f64,   // 终止时残差
+    pub converged: bool, // 是否收敛
+}
+
+// ============ 气液两相流 ============
+#[derive(Deserialize)]
+pub struct CalculateMultiphaseReq {
+    pub well_no: Option<String>, // 有井斜数据时按井眼轨迹换算垂深
+    pub rg: f64,
+    pub pc: Option<f64>, // 缺省时由 rg 计算
+    pub tc: Option<f64>,
+    pub sections: Vec<TubingSection>, // 管柱分段（长度为测深），自井口向下
+    #[serde(default)]
+    pub ee: f64, // 绝对粗糙度，m
+    #[serde(default)]
+    pub friction: FrictionMethod, // 摩阻系数计算方法
+    pub qg: f64,                      // 产气量，m³/d
+    #[serde(flatten)]
+    pub liquids: Liquids, // 水气比 wgr、凝析油气比 cgr（m³/10⁴m³）及液相物性
+    pub step: f64,                    // 测点间距，m
     pub tts: f64,
     pub tws: f64,
+    #[serde(default)]
+    pub temperature: TemperatureModel,
     pub pts: f64,
+    #[serde(default)]
+    pub n2: f64,
+    #[serde(default)]
+    pub co2: f64,
+    #[serde(default)]
+    pub h2s: f64,
+    #[serde(default)]
+    pub method: MultiphaseMethod,
+    #[serde(default)]
+    pub z_method: ZMethod,
+    #[serde(default)]
+    pub pc_method: PcMethod,
+    #[serde(default)]
+    pub sour_correction: SourCorrection,
+}
+
+#[derive(Serialize)]
+pub struct MultiphaseResp {
+    pub pwf: f64, // 井底流压，MPa
+    pub points: Vec<MultiphasePoint>,
+    pub method: &'static str,      // 所用两相流关联式
+    pub temperature: &'static str, // 所用井筒温度模型
+    pub z_method: &'static str,
+    pub pc: f64,
+    pub tc: f64,
+    pub iterations: u32, // 推算步数
+    pub residual: f64,   // 终止时残差
+    pub converged: bool, // 是否收敛
+}
+
+// ============ 批量计算行结果 ============
+/// 单行计算错误
+#[derive(Serialize)]
+pub struct RowError {
+    pub code: &'static str, // 错误代码
+    pub message: String,    // 错误说明
+}
+
+impl From<CalcError> for RowError {
+    fn from(e: CalcError) -> Self {
+        RowError {
+            code: e.code(),
+            message: e.to_string(),
+        }
+    }
+}
+
+/// 批量计算的单行结果：成功时为计算值，失败时为 {"error": {...}}
+#[derive(Serialize)]
+#[serde(untagged)]
+pub enum BatchRow<T> {
+    Ok(T),
+    Err { error: RowError },
+}
+
+impl<T> From<Result<T, CalcError>> for BatchRow<T> {
+    fn from(r: Result<T, CalcError>) -> Self {
+        match r {
+            Ok(v) => BatchRow::Ok(v),
+            Err(e) => BatchRow::Err { error: e.into() },
+        }
+    }
 }
 
 // ============ 批量PVT ============
 #[derive(Deserialize)]
 pub struct CalculateBatchPVTReq {
     pub pressures: Vec<f64>,
-    pub pc: f64,
-    pub tc: f64,
+    pub pc: Option<f64>, // 缺省时由 rg 计算
+    pub tc: Option<f64>,
     pub t: f64,
     pub rg: f64,
     pub n2: f64,
     pub co2: f64,
     pub h2s: f64,
+    #[serde(default)]
+    pub z_method: ZMethod,
+    #[serde(default)]
+    pub pc_method: PcMethod,
+    #[serde(default)]
+    pub sour_correction: SourCorrection,
+    pub aga8: Option<Aga8Method>, // 给出时按 AGA8 计算 z、密度、cg
+    pub components: Option<Vec<ComponentFraction>>, // AGA8 详细特征法所需组分分析
+    pub c_factor: Option<f64>,    // 冲蚀流速经验常数 C，缺省为 100
 }
 
 #[derive(Serialize)]
@@ -105,21 +376,36 @@ pub struct BatchPVTResp {
     pub niandu: f64,
     pub cg: f64,
     pub density: f64,
+    pub ve: f64,                // 冲蚀流速，m/s（API RP 14E）
+    pub z_method: &'static str, // 所用 Z 因子关联式（或 AGA8 方法）
+    pub pc: f64,                // 计算所用拟临界压力（酸性气体校正后）
+    pub tc: f64,                // 计算所用拟临界温度（酸性气体校正后）
+    pub iterations: u32,        // 迭代次数
+    pub residual: f64,          // 终止时残差
+    pub converged: bool,        // 是否收敛
 }
 
 // ============ 批量PB ============
 #[derive(Deserialize)]
 pub struct CalculateBatchPbReq {
+    pub well_no: Option<String>, // 有井斜数据时按井眼轨迹换算垂深
     pub pts: Vec<f64>,
     pub rg: f64,
-    pub pc: f64,
-    pub tc: f64,
-    pub h: f64,
+    pub pc: Option<f64>, // 缺省时由 rg 计算
+    pub tc: Option<f64>,
+    pub h: f64, // 中部井深（测深），m
     pub tts: f64,
     pub tws: f64,
     pub n2: f64,
     pub co2: f64,
     pub h2s: f64,
+    #[serde(default)]
+    pub z_method: ZMethod,
+    #[serde(default)]
+    pub pc_method: PcMethod,
+    #[serde(default)]
+    pub sour_correction: SourCorrection,
+    pub method: Option<BhpMethod>, // 缺省为平均温度平均偏差系数法
 }
 
 #[derive(Serialize)]
@@ -130,21 +416,506 @@ pub struct BatchPbResp {
     pub bg: f64,
     pub niandu: f64,
     pub cg: f64,
+    pub method: &'static str,   // 所用井底静压计算方法
+    pub z_method: &'static str, // 所用 Z 因子关联式
+    pub pc: f64,                // 计算所用拟临界压力（酸性气体校正后）
+    pub tc: f64,                // 计算所用拟临界温度（酸性气体校正后）
+    pub iterations: u32,        // 迭代次数
+    pub residual: f64,          // 终止时残差
+    pub converged: bool,        // 是否收敛
+}
+
+/// 井底流压批量计算中的一个工作点
+#[derive(Deserialize, Debug, Clone, Copy)]
+pub struct PwfPoint {
+    pub pt: f64, // 井口流压，MPa
+    pub qg: f64, // 产气量，m³/d
 }
 
 #[derive(Deserialize)]
-pub struct CalculateBatchPhReq {
-    pub pwbs: Vec<f64>, // 井底压力数组
+pub struct CalculateBatchPwfReq {
+    pub well_no: Option<String>, // 有井斜数据时按井眼轨迹换算垂深
+    pub points: Vec<PwfPoint>,
+    pub sections: Vec<TubingSection>, // 管柱分段（长度为测深），自井口向下
+    pub ee: f64,                      // 绝对粗糙度，m
+    #[serde(default)]
+    pub friction: FrictionMethod, // 摩阻系数计算方法
+    pub rg: f64,
+    pub pc: Option<f64>, // 缺省时由 rg 计算
+    pub tc: Option<f64>,
+    pub tts: f64, // 井口流温，K
+    pub tws: f64, // 井底流温，K
+    #[serde(default)]
+    pub temperature: TemperatureModel, // 井筒温度模型，缺省为线性分布
+    pub n2: f64,
+    pub co2: f64,
+    pub h2s: f64,
+    #[serde(default)]
+    pub method: BhpMethod,
+    #[serde(default)]
+    pub z_method: ZMethod,
+    #[serde(default)]
+    pub pc_method: PcMethod,
+    #[serde(default)]
+    pub sour_correction: SourCorrection,
+}
+
+#[derive(Serialize)]
+pub struct BatchPwfResp {
+    pub pt: f64,
+    pub qg: f64,
+    pub pwf: f64,
+    pub z: f64,
+    pub p_over_z: f64,
+    pub bg: f64,
+    pub niandu: f64,
+    pub cg: f64,
+    pub method: &'static str,   // 所用井底流压计算方法
+    pub z_method: &'static str, // 所用 Z 因子关联式
+    pub pc: f64,                // 计算所用拟临界压力（酸性气体校正后）
+    pub tc: f64,                // 计算所用拟临界温度（酸性气体校正后）
+    pub iterations: u32,        // 迭代次数
+    pub residual: f64,          // 终止时残差
+    pub converged: bool,        // 是否收敛
+}
+
+/// 积液分析中的一条日生产记录
+#[derive(Deserialize, Debug, Clone)]
+pub struct LoadingRecord {
+    #[serde(default)]
+    pub well_no: String,
+    #[serde(default)]
+    pub date: String,
+    pub pt: f64, // 井口流压，MPa
+    pub tt: f64, // 井口流温，K
+    pub qg: f64, // 日产气量，m³/d
+}
+
+#[derive(Deserialize)]
+pub struct CalculateLiquidLoadingReq {
+    pub records: Vec<LoadingRecord>,
+    pub d: f64, // 油管内径，m
+    pub rg: f64,
+    pub pc: Option<f64>, // 缺省时由 rg 计算
+    pub tc: Option<f64>,
+    #[serde(default)]
+    pub n2: f64,
+    #[serde(default)]
+    pub co2: f64,
+    #[serde(default)]
+    pub h2s: f64,
+    #[serde(default)]
+    pub model: LoadingModel,
+    #[serde(default)]
+    pub liquid: Liquid,
+    pub rho_l: Option<f64>, // 液相密度，kg/m³，缺省按 liquid 取值
+    pub sigma: Option<f64>, // 表面张力，N/m，缺省按 liquid 取值
+    #[serde(default)]
+    pub z_method: ZMethod,
+    #[serde(default)]
+    pub pc_method: PcMethod,
+    #[serde(default)]
+    pub sour_correction: SourCorrection,
+}
+
+#[derive(Serialize)]
+pub struct LiquidLoadingResp {
     pub well_no: String,
+    pub date: String,
+    pub z: f64,
+    pub density: f64,        // 井口气体密度，kg/m³
+    pub vc: f64,             // 临界携液流速，m/s
+    pub qc: f64,             // 临界携液气量，m³/d
+    pub v: f64,              // 井口实际气体流速，m/s
+    pub loading: bool,       // 日产气量低于临界携液气量，井筒积液
+    pub model: &'static str, // 所用临界携液模型
+}
+
+/// 油管尺寸优选的候选管柱
+#[derive(Deserialize, Debug, Clone)]
+pub struct TubingCandidate {
+    #[serde(default)]
+    pub name: String, // 如 2-3/8″ 油管、1.5″ 连续油管
+    pub d: f64, // 内径，m
+}
+
+#[derive(Deserialize)]
+pub struct CalculateTubingStudyReq {
+    pub well_no: String, // 井数据取自 gaswell 表
+    pub candidates: Vec<TubingCandidate>,
+    pub pt: f64, // 井口流压，MPa
+    pub qg: f64, // 产气量，m³/d
+    #[serde(default)]
+    pub ee: f64, // 绝对粗糙度，m
+    #[serde(default)]
+    pub friction: FrictionMethod, // 摩阻系数计算方法
+    #[serde(default)]
+    pub method: BhpMethod,
+    #[serde(default)]
+    pub temperature: TemperatureModel,
+    #[serde(default)]
+    pub model: LoadingModel,
+    #[serde(default)]
+    pub liquid: Liquid,
+    pub rho_l: Option<f64>, // 液相密度，kg/m³，缺省按 liquid 取值
+    pub sigma: Option<f64>, // 表面张力，N/m，缺省按 liquid 取值
+    #[serde(default)]
+    pub z_method: ZMethod,
+    #[serde(default)]
+    pub pc_method: PcMethod,
+    #[serde(default)]
+    pub sour_correction: SourCorrection,
+}
+
+#[derive(Serialize)]
+pub struct TubingStudyResp {
+    pub name: String,
+    pub d: f64,
+    pub pwf: f64,            // 井底流压，MPa
+    pub friction_loss: f64,  // 摩阻压降，MPa
+    pub v_top: f64,          // 井口气体流速，m/s
+    pub v_bottom: f64,       // 井底气体流速，m/s
+    pub vc: f64,             // 井口临界携液流速，m/s
+    pub qc: f64,             // 井口临界携液气量，m³/d
+    pub loading: bool,       // 产气量低于临界携液气量
+    pub model: &'static str, // 所用临界携液模型
+    pub iterations: u32,     // 迭代次数
+    pub residual: f64,       // 终止时残差
+    pub converged: bool,     // 是否收敛
+}
+
+#[derive(Deserialize)]
+pub struct CalculateErosionReq {
+    pub qg: f64,               // 产气量，m³/d
+    pub d: f64,                // 油管或集输管线内径，m
+    pub pt: f64,               // 井口流压，MPa
+    pub tt: f64,               // 井口流温，K
+    pub pwf: f64,              // 井底流压，MPa
+    pub twf: f64,              // 井底流温，K
+    pub c_factor: Option<f64>, // 经验常数 C，缺省为 100
+    pub rg: f64,
+    pub pc: Option<f64>, // 缺省时由 rg 计算
+    pub tc: Option<f64>,
+    #[serde(default)]
+    pub n2: f64,
+    #[serde(default)]
+    pub co2: f64,
+    #[serde(default)]
+    pub h2s: f64,
+    #[serde(default)]
+    pub z_method: ZMethod,
+    #[serde(default)]
+    pub pc_method: PcMethod,
+    #[serde(default)]
+    pub sour_correction: SourCorrection,
+}
+
+#[derive(Serialize)]
+pub struct ErosionResp {
+    pub location: &'static str, // 井口 / 井底
+    pub p: f64,
+    pub t: f64,
+    pub z: f64,
+    pub density: f64,   // 气体密度，kg/m³
+    pub v: f64,         // 实际气体流速，m/s
+    pub ve: f64,        // 冲蚀流速，m/s
+    pub qe: f64,        // 冲蚀流速对应的气量，m³/d
+    pub exceeded: bool, // 实际流速超过冲蚀流速
+}
+
+// ============ 物质平衡 ============
+#[derive(Deserialize)]
+pub struct CalculateMaterialBalanceReq {
+    pub history: Vec<MbPoint>, // 地层压力与累计产气量（10⁸m³）
+    pub t: f64,                // 地层温度，K
+    pub pi: Option<f64>,       // 原始地层压力，MPa，缺省取累产最小点的压力
+    pub pa: Option<f64>,       // 废弃压力，MPa，给出时计算采收率
+    #[serde(default)]
+    pub method: MbMethod,
+    #[serde(flatten)]
+    pub rock: Compressibility, // cf、cw（1/MPa）、swi 及 Fetkovich 体积比 m
     pub rg: f64,
+    pub pc: Option<f64>, // 缺省时由 rg 计算
+    pub tc: Option<f64>,
+    #[serde(default)]
+    pub n2: f64,
+    #[serde(default)]
+    pub co2: f64,
+    #[serde(default)]
+    pub h2s: f64,
+    #[serde(default)]
+    pub z_method: ZMethod,
+    #[serde(default)]
+    pub pc_method: PcMethod,
+    #[serde(default)]
+    pub sour_correction: SourCorrection,
+}
+
+#[derive(Serialize)]
+pub struct MaterialBalanceResp {
+    pub ogip: f64,                  // 原始地质储量，10⁸m³
+    pub apparent_ogip: Option<f64>, // 未经校正的 p/Z 外推储量（异常高压方法）
+    pub pi: f64,                    // 原始地层压力，MPa
+    pub p_over_z_i: f64,            // 原始 p/Z，MPa
+    pub ce: f64,                    // 有效压缩系数，1/MPa
+    pub fit: LinearFit,             // 回归统计（Roach 法为 Roach 坐标下的直线）
+    pub p_over_z_a: Option<f64>,    // 废弃压力下的 p/Z
+    pub gp_a: Option<f64>,          // 废弃时累计产气量（可采储量），10⁸m³
+    pub recovery: Option<f64>,      // 采收率
+    pub points: Vec<PzPoint>,
+    pub line: Vec<LinePoint>, // 拟合直线（校正后的 p/Z）
+    pub method: &'static str, // 所用物质平衡方法
+    pub z_method: &'static str,
+    pub pc: f64,
+    pub tc: f64,
+}
+
+#[derive(Deserialize)]
+pub struct CalculateWaterDriveReq {
+    pub history: Vec<WaterDrivePoint>, // 生产时间（d）、地层压力、累计产气量（10⁸m³）、累计产水量（10⁴m³）
+    pub t: f64,                        // 地层温度，K
+    pub pi: Option<f64>,               // 原始地层压力，MPa，缺省取第一个点的压力
+    pub bw: Option<f64>,               // 地层水体积系数，缺省为 1
+    #[serde(default)]
+    pub model: AquiferModel,
+    #[serde(default)]
+    pub aquifer: AquiferGeometry, // 非稳态水体形状
+    pub rd: Option<f64>,      // 径向水体外半径与气藏半径之比，缺省时优选
+    pub td_coef: Option<f64>, // 无因次时间系数 tD/t（1/d），缺省时拟合
+    #[serde(flatten)]
+    pub rock: Compressibility, // cf、cw（1/MPa）、swi
+    pub rg: f64,
+    pub pc: Option<f64>, // 缺省时由 rg 计算
+    pub tc: Option<f64>,
+    #[serde(default)]
+    pub n2: f64,
+    #[serde(default)]
+    pub co2: f64,
+    #[serde(default)]
+    pub h2s: f64,
+    #[serde(default)]
+    pub z_method: ZMethod,
+    #[serde(default)]
+    pub pc_method: PcMethod,
+    #[serde(default)]
+    pub sour_correction: SourCorrection,
+}
+
+#[derive(Serialize)]
+pub struct WaterDriveResp {
+    pub ogip: f64,            // 原始地质储量，10⁸m³
+    pub volumetric_ogip: f64, // 不计水侵时拟合的储量，10⁸m³
+    pub params: AquiferParams,
+    pub r2: f64,  // 地下采出量拟合的决定系数
+    pub pi: f64,  // 原始地层压力，MPa
+    pub bgi: f64, // 原始气体体积系数
+    pub rows: Vec<WaterDriveRow>,
+    pub model: &'static str,   // 所用水侵模型
+    pub aquifer: &'static str, // 非稳态水体形状
+    pub z_method: &'static str,
+    pub pc: f64,
+    pub tc: f64,
+}
+
+// ============ 压力折算到基准面 ============
+/// 压力计读数
+#[derive(Deserialize, Debug, Clone, Copy)]
+pub struct GaugeReading {
+    pub p: f64,  // 实测压力，MPa
+    pub md: f64, // 压力计下入深度（测深），m
+    pub t: f64,  // 实测温度，K
+}
+
+#[derive(Deserialize)]
+pub struct CalculateDatumReq {
+    pub well_no: Option<String>, // 有井斜数据时按井眼轨迹换算垂深
+    pub readings: Vec<GaugeReading>,
+    pub elevation: f64,       // 补心海拔，m
+    pub datum: f64,           // 基准面海拔（海平面以下为负，如 -4500），m
+    pub t_grad: Option<f64>,  // 地温梯度，K/100m，缺省为 3
+    pub contact: Option<f64>, // 气水（气油）界面海拔，m，界面以下按液柱折算
+    #[serde(default)]
+    pub liquid: Liquid,
+    pub rho_l: Option<f64>, // 液相密度，kg/m³，缺省按 liquid 取值
+    pub rg: f64,
+    pub pc: Option<f64>, // 缺省时由 rg 计算
+    pub tc: Option<f64>,
+    #[serde(default)]
+    pub n2: f64,
+    #[serde(default)]
+    pub co2: f64,
+    #[serde(default)]
+    pub h2s: f64,
+    #[serde(default)]
+    pub z_method: ZMethod,
+    #[serde(default)]
+    pub pc_method: PcMethod,
+    #[serde(default)]
+    pub sour_correction: SourCorrection,
+}
+
+#[derive(Serialize)]
+pub struct DatumResp {
+    pub md: f64,                // 压力计测深，m
+    pub tvd: f64,               // 压力计垂深，m
+    pub gauge_elevation: f64,   // 压力计海拔，m
+    pub p_gauge: f64,           // 实测压力，MPa
+    pub p_datum: f64,           // 基准面压力，MPa
+    pub t_datum: f64,           // 基准面温度，K
+    pub gas_column: f64,        // 按气柱折算的高差（向下为正），m
+    pub liquid_column: f64,     // 按液柱折算的高差（向下为正），m
+    pub z_method: &'static str, // 所用 Z 因子关联式
+    pub pc: f64,
+    pub tc: f64,
+    pub iterations: u32, // 迭代次数
+    pub residual: f64,   // 终止时残差
+    pub converged: bool, // 是否收敛
+}
+
+// ============ 摩阻系数 ============
+/// 管流工况点
+#[derive(Deserialize, Debug, Clone, Copy)]
+pub struct FlowPoint {
+    pub p: f64,  // 压力，MPa
+    pub t: f64,  // 温度，K
+    pub qg: f64, // 产气量，m³/d
+}
+
+#[derive(Deserialize)]
+pub struct CalculateFrictionReq {
+    pub points: Vec<FlowPoint>,
+    pub d: f64, // 油管或管线内径，m
+    #[serde(default)]
+    pub ee: f64, // 绝对粗糙度，m
+    #[serde(default)]
+    pub method: FrictionMethod,
+    pub rg: f64,
+    pub pc: Option<f64>, // 缺省时由 rg 计算
+    pub tc: Option<f64>,
+    #[serde(default)]
+    pub n2: f64,
+    #[serde(default)]
+    pub co2: f64,
+    #[serde(default)]
+    pub h2s: f64,
+    #[serde(default)]
+    pub z_method: ZMethod,
+    #[serde(default)]
+    pub pc_method: PcMethod,
+    #[serde(default)]
+    pub sour_correction: SourCorrection,
+}
+
+#[derive(Serialize)]
+pub struct FrictionResp {
+    pub z: f64,
+    pub niandu: f64,            // 粘度，mPa·s
+    pub re: f64,                // 雷诺数
+    pub f: f64,                 // 摩阻系数（Moody）
+    pub method: &'static str,   // 所用摩阻系数公式
+    pub z_method: &'static str, // 所用 Z 因子关联式
+    pub pc: f64,
+    pub tc: f64,
+}
+
+// ============ 油嘴流动 ============
+/// 已知油嘴直径求产气量的计算点
+#[derive(Deserialize, Debug, Clone, Copy)]
+pub struct ChokeRatePoint {
+    pub p1: f64, // 嘴前（上游）压力，MPa
+    pub p2: f64, // 嘴后（下游）压力，MPa
+    pub t1: f64, // 嘴前温度，K
+    pub d: f64,  // 油嘴直径，mm
+}
+
+/// 已知目标产气量求油嘴直径的计算点
+#[derive(Deserialize, Debug, Clone, Copy)]
+pub struct ChokeSizePoint {
+    pub p1: f64, // 嘴前（上游）压力，MPa
+    pub p2: f64, // 嘴后（下游）压力，MPa
+    pub t1: f64, // 嘴前温度，K
+    pub qg: f64, // 目标产气量，m³/d
+}
+
+/// 油嘴计算点的嘴前条件
+pub trait ChokeInlet {
+    /// 嘴前压力（MPa）、温度（K）
+    fn inlet(&self) -> (f64, f64);
+}
+
+impl ChokeInlet for ChokeRatePoint {
+    fn inlet(&self) -> (f64, f64) {
+        (self.p1, self.t1)
+    }
+}
+
+impl ChokeInlet for ChokeSizePoint {
+    fn inlet(&self) -> (f64, f64) {
+        (self.p1, self.t1)
+    }
+}
+
+#[derive(Deserialize)]
+pub struct CalculateChokeReq<P> {
+    pub points: Vec<P>,
+    pub rg: f64,
+    pub pc: Option<f64>, // 缺省时由 rg 计算
+    pub tc: Option<f64>,
+    #[serde(default)]
+    pub n2: f64,
+    #[serde(default)]
+    pub co2: f64,
+    #[serde(default)]
+    pub h2s: f64,
+    #[serde(default)]
+    pub method: ChokeMethod,
+    pub k: Option<f64>,  // 绝热指数，缺省为 1.3
+    pub cd: Option<f64>, // 流量系数，缺省为 0.86
+    #[serde(default)]
+    pub z_method: ZMethod,
+    #[serde(default)]
+    pub pc_method: PcMethod,
+    #[serde(default)]
+    pub sour_correction: SourCorrection,
+}
+
+#[derive(Serialize)]
+pub struct ChokeResp {
+    pub qg: f64,                // 产气量，m³/d
+    pub d: f64,                 // 油嘴直径，mm
+    pub z: f64,                 // 嘴前偏差因子
+    pub ratio: f64,             // 压力比 p2/p1
+    pub critical_ratio: f64,    // 临界压力比
+    pub regime: &'static str,   // 临界流 / 亚临界流
+    pub method: &'static str,   // 所用油嘴流动方程
+    pub z_method: &'static str, // 所用 Z 因子关联式
     pub pc: f64,
     pub tc: f64,
-    pub h: f64,
+    pub iterations: u32, // 迭代次数
+    pub residual: f64,   // 终止时残差
+    pub converged: bool, // 是否收敛
+}
+
+#[derive(Deserialize)]
+pub struct CalculateBatchPhReq {
+    pub pwbs: Vec<f64>, // 井底压力数组
+    #[serde(default)]
+    pub well_no: String, // 按该井的井眼轨迹换算垂深，为空时按直井处理
+    pub rg: f64,
+    pub pc: Option<f64>, // 缺省时由 rg 计算
+    pub tc: Option<f64>,
+    pub h: f64, // 中部井深（测深），m
     pub tts: f64,
     pub tws: f64,
     pub n2: f64,
     pub co2: f64,
     pub h2s: f64,
+    #[serde(default)]
+    pub z_method: ZMethod,
+    #[serde(default)]
+    pub pc_method: PcMethod,
+    #[serde(default)]
+    pub sour_correction: SourCorrection,
 }
 
 #[derive(Serialize)]
@@ -155,4 +926,197 @@ pub struct BatchPhResp {
     pub bg: f64,
     pub niandu: f64,
     pub cg: f64,
+    pub z_method: &'static str, // 所用 Z 因子关联式
+    pub pc: f64,                // 计算所用拟临界压力（酸性气体校正后）
+    pub tc: f64,                // 计算所用拟临界温度（酸性气体校正后）
+    pub iterations: u32,        // 迭代次数
+    pub residual: f64,          // 终止时残差
+    pub converged: bool,        // 是否收敛
+}
+
+// ============ 产能试井 ============
+#[derive(Deserialize)]
+pub struct CalculateDeliverabilityReq {
+    pub points: Vec<TestPoint>, // 产气量（10⁴m³/d）、井底流压、开井前关井压力、是否延时稳定点
+    pub pr: f64,                // 平均地层压力，MPa
+    pub t: f64,                 // 地层温度，K
+    #[serde(default)]
+    pub test: DeliverabilityTest,
+    #[serde(default)]
+    pub form: PotentialForm,
+    pub rg: f64,
+    pub pc: Option<f64>, // 缺省时由 rg 计算
+    pub tc: Option<f64>,
+    #[serde(default)]
+    pub n2: f64,
+    #[serde(default)]
+    pub co2: f64,
+    #[serde(default)]
+    pub h2s: f64,
+    #[serde(default)]
+    pub z_method: ZMethod,
+    #[serde(default)]
+    pub pc_method: PcMethod,
+    #[serde(default)]
+    pub sour_correction: SourCorrection,
+}
+
+#[derive(Serialize)]
+pub struct DeliverabilityResp {
+    pub aof_cn: f64,  // 指数式方程无阻流量，10⁴m³/d
+    pub aof_lit: f64, // 二项式方程无阻流量，10⁴m³/d
+    pub cn: CnCoefficients,
+    pub lit: LitCoefficients,
+    pub pr: f64,
+    pub rows: Vec<TestRow>,
+    pub ipr: Vec<IprPoint>,
+    pub test: &'static str, // 试井类型
+    pub form: &'static str, // 压力函数形式
+    pub z_method: &'static str,
+    pub pc: f64,
+    pub tc: f64,
+}
+
+// ============ 拟压力 ============
+#[derive(Deserialize)]
+pub struct PressureHistoryPoint {
+    pub time: f64, // 时间（单位自定，拟时间与之一致）
+    pub p: f64,    // 压力，MPa
+}
+
+#[derive(Deserialize)]
+pub struct CalculatePseudoPressureReq {
+    pub pressures: Vec<f64>,
+    pub pc: Option<f64>, // 缺省时由 rg 计算
+    pub tc: Option<f64>,
+    pub t: f64,
+    pub rg: f64,
+    #[serde(default)]
+    pub n2: f64,
+    #[serde(default)]
+    pub co2: f64,
+    #[serde(default)]
+    pub h2s: f64,
+    #[serde(default)]
+    pub z_method: ZMethod,
+    #[serde(default)]
+    pub pc_method: PcMethod,
+    #[serde(default)]
+    pub sour_correction: SourCorrection,
+    pub p_ref: Option<f64>, // 规整化参考压力，缺省取最高压力
+    #[serde(default)]
+    pub history: Vec<PressureHistoryPoint>, // 压力历史，用于计算拟时间
+}
+
+#[derive(Serialize)]
+pub struct PseudoPressureRow {
+    pub p: f64,
+    pub mu: f64, // 粘度，mPa·s
+    pub z: f64,
+    pub mp: f64,     // 拟压力 m(p)，MPa²/(mPa·s)
+    pub p_norm: f64, // 规整化拟压力，MPa
+}
+
+#[derive(Serialize)]
+pub struct PseudoTimeRow {
+    pub time: f64,
+    pub p: f64,
+    pub mu: f64,
+    pub cg: f64,
+    pub ta: f64,      // 拟时间
+    pub ta_norm: f64, // 规整化拟时间
+}
+
+#[derive(Serialize)]
+pub struct PseudoPressureResp {
+    pub p_ref: f64,
+    pub table: Vec<PseudoPressureRow>,
+    pub pseudo_time: Vec<PseudoTimeRow>,
+}
+
+// ============ 组分分析 ============
+#[derive(Deserialize)]
+pub struct ComponentFraction {
+    pub name: ComponentId, // 组分代号，如 C1、iC4、C7+、CO2
+    pub y: f64,            // 摩尔分数
+}
+
+#[derive(Deserialize)]
+pub struct CalculateCompositionReq {
+    pub components: Vec<ComponentFraction>,
+    pub c7plus_mw: Option<f64>, // C7+ 分子量（含 C7+ 时必填）
+    pub c7plus_sg: Option<f64>, // C7+ 相对密度（含 C7+ 时必填）
+    #[serde(default)]
+    pub sour_correction: SourCorrection,
+}
+
+#[derive(Serialize)]
+pub struct CompositionResp {
+    pub total: f64, // 输入摩尔分数总和（计算前已归一化）
+    pub mw: f64,    // 视分子量
+    pub rg: f64,    // 相对密度
+    pub pc: f64,    // Kay 规则拟临界压力，MPa
+    pub tc: f64,    // Kay 规则拟临界温度，K
+    pub n2: f64,
+    pub co2: f64,
+    pub h2s: f64,
+    pub c7plus: Option<PlusFraction>,
+}
+
+// ============ 状态方程闪蒸 ============
+#[derive(Deserialize)]
+pub struct CalculateEosFlashReq {
+    pub components: Vec<ComponentFraction>,
+    pub c7plus_mw: Option<f64>,
+    pub c7plus_sg: Option<f64>,
+    pub t: f64,              // 温度，K
+    pub pressures: Vec<f64>, // 压力，MPa
+    #[serde(default)]
+    pub eos: EosModel,
+    pub kij: Option<Vec<Vec<f64>>>, // 二元交互作用系数，顺序与 components 一致
+}
+
+#[derive(Serialize)]
+pub struct EosFlashResp {
+    pub p: f64,
+    pub stable: bool,        // 单相稳定
+    pub vapor_fraction: f64, // 气相摩尔分数
+    pub z_v: Option<f64>,
+    pub z_l: Option<f64>,
+    pub density_v: Option<f64>, // kg/m³
+    pub density_l: Option<f64>,
+    pub y: Vec<f64>,                  // 气相组成
+    pub x: Vec<f64>,                  // 液相组成
+    pub fugacity_v: Option<Vec<f64>>, // MPa
+    pub fugacity_l: Option<Vec<f64>>,
+    pub iterations: u32, // 逐次替代迭代次数
+    pub residual: f64,   // 终止时残差
+    pub converged: bool, // 是否收敛
+}
+
+// ============ AGA8 ============
+#[derive(Deserialize)]
+pub struct CalculateZAga8Req {
+    pub pressures: Vec<f64>, // 压力，MPa
+    pub t: f64,              // 温度，K
+    #[serde(default)]
+    pub method: Aga8Method,
+    pub components: Option<Vec<ComponentFraction>>, // 详细特征法必填，不支持 C7+
+    pub rg: Option<f64>,                            // 物性值法：标准参比条件下真实相对密度
+    #[serde(default)]
+    pub n2: f64,
+    #[serde(default)]
+    pub co2: f64,
+}
+
+#[derive(Serialize)]
+pub struct Aga8Resp {
+    pub p: f64,
+    pub z: f64,
+    pub density: f64, // kg/m³
+    pub cg: f64,      // 1/MPa
+    pub method: &'static str,
+    pub iterations: u32, // 迭代次数
+    pub residual: f64,   // 终止时残差
+    pub converged: bool, // 是否收敛
 }
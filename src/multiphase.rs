@@ -0,0 +1,437 @@
+use crate::friction::{self, FrictionMethod};
+use crate::pressure::{self, CalcError, GasProps, Path, TubingSection};
+use crate::solver::Solution;
+use crate::temperature::TemperatureModel;
+use crate::trajectory::Trajectory;
+use serde::{Deserialize, Serialize};
+use std::f64::consts::PI;
+
+// 重力加速度，m/s²
+const G: f64 = 9.81;
+// 地面标准状况（20 ℃、0.101325 MPa）
+const T_SC: f64 = 293.15;
+const P_SC: f64 = 0.101325;
+// 压力推算的最大步长，m
+const MAX_DL: f64 = 25.0;
+
+/// 气液两相管流关联式
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum MultiphaseMethod {
+    /// Gray（API 14B），适用于凝析气井、产水气井（默认）
+    #[default]
+    Gray,
+    /// Beggs-Brill（含 Payne 校正），适用于任意井斜
+    BeggsBrill,
+    /// Hagedorn-Brown（修正），适用于垂直井
+    HagedornBrown,
+}
+
+impl MultiphaseMethod {
+    pub fn name(self) -> &'static str {
+        match self {
+            MultiphaseMethod::Gray => "Gray",
+            MultiphaseMethod::BeggsBrill => "Beggs-Brill",
+            MultiphaseMethod::HagedornBrown => "Hagedorn-Brown",
+        }
+    }
+}
+
+/// 产出液性质，水气比、凝析油气比单位为 m³/10⁴m³
+#[derive(Deserialize, Debug, Clone, Copy)]
+#[serde(default)]
+pub struct Liquids {
+    pub wgr: f64,     // 水气比
+    pub cgr: f64,     // 凝析油气比
+    pub rho_w: f64,   // 水密度，kg/m³
+    pub rho_o: f64,   // 凝析油密度，kg/m³
+    pub mu_w: f64,    // 水粘度，mPa·s
+    pub mu_o: f64,    // 凝析油粘度，mPa·s
+    pub sigma_w: f64, // 气水表面张力，N/m
+    pub sigma_o: f64, // 气油表面张力，N/m
+}
+
+impl Default for Liquids {
+    fn default() -> Self {
+        Liquids {
+            wgr: 0.0,
+            cgr: 0.0,
+            rho_w: 1030.0,
+            rho_o: 750.0,
+            mu_w: 0.5,
+            mu_o: 0.5,
+            sigma_w: 0.06,
+            sigma_o: 0.02,
+        }
+    }
+}
+
+impl Liquids {
+    // 按体积分数加权的液相密度（kg/m³）、粘度（Pa·s）及表面张力（N/m）
+    // Gray 法表面张力按 (qo·σo + 0.617·qw·σw)/(qo + 0.617·qw) 计算
+    fn mixture(&self, method: MultiphaseMethod) -> (f64, f64, f64) {
+        let ql = self.wgr + self.cgr;
+        if ql <= 0.0 {
+            return (self.rho_w, self.mu_w * 1e-3, self.sigma_w);
+        }
+        let (fw, fo) = (self.wgr / ql, self.cgr / ql);
+        let sigma = match method {
+            MultiphaseMethod::Gray => {
+                (self.cgr * self.sigma_o + 0.617 * self.wgr * self.sigma_w)
+                    / (self.cgr + 0.617 * self.wgr)
+            }
+            _ => fw * self.sigma_w + fo * self.sigma_o,
+        };
+        (
+            fw * self.rho_w + fo * self.rho_o,
+            (fw * self.mu_w + fo * self.mu_o) * 1e-3,
+            sigma,
+        )
+    }
+}
+
+/// 两相流压力剖面中的一个测点
+#[derive(Serialize, Debug, Clone, Copy)]
+pub struct MultiphasePoint {
+    pub md: f64,                       // 测深，m
+    pub tvd: f64,                      // 垂深，m
+    pub p: f64,                        // 压力，MPa
+    pub t: f64,                        // 温度，K
+    pub z: f64,                        //
+    pub holdup: f64,                   // 持液率
+    pub density: f64,                  // 混合物密度（考虑滑脱），kg/m³
+    pub gradient: f64,                 // 压力梯度，MPa/100m
+    pub pattern: Option<&'static str>, // 流型（Beggs-Brill）
+}
+
+// 某一截面的流动状态
+struct Local {
+    gradient: f64, // Pa/m
+    holdup: f64,
+    density: f64,
+    z: f64,
+    pattern: Option<&'static str>,
+}
+
+/// 自井口向下计算气液两相流井底流压及沿程持液率
+/// 参数:
+///   gas         – 气体参数
+///   liquids     – 产出液性质
+///   method      – 两相流关联式
+///   sections    – 管柱分段（长度为测深），自井口向下
+///   trajectory  – 井眼轨迹
+///   temperature – 井筒温度模型
+///   ee          – 绝对粗糙度，m
+///   friction    – 摩阻系数计算方法
+///   tts、tws    – 井口、井底温度，K
+///   pts         – 井口流压，MPa
+///   qg          – 产气量，m³/d（标准状况）
+///   step        – 测点间距，m
+/// 返回:
+///   各测点参数；value 为井底流压，iterations 为推算步数
+#[allow(clippy::too_many_arguments)]
+pub fn traverse(
+    gas: &GasProps,
+    liquids: &Liquids,
+    method: MultiphaseMethod,
+    sections: &[TubingSection],
+    trajectory: &Trajectory,
+    temperature: &TemperatureModel,
+    ee: f64,
+    friction: FrictionMethod,
+    tts: f64,
+    tws: f64,
+    pts: f64,
+    qg: f64,
+    step: f64,
+) -> Result<(Vec<MultiphasePoint>, Solution), CalcError> {
+    pressure::check_pressure(pts)?;
+    pressure::check_flow(sections, qg)?;
+    if step <= 0.0 {
+        return Err(CalcError::InvalidInput("测点间距须大于 0"));
+    }
+    if liquids.wgr < 0.0 || liquids.cgr < 0.0 {
+        return Err(CalcError::InvalidInput("水气比、凝析油气比不能为负"));
+    }
+    let path = Path::new(sections, trajectory, temperature, tts, tws, gas.rg, qg);
+    let (rho_l, mu_l, sigma) = liquids.mixture(method);
+    // 液相体积流量，m³/s
+    let ql = qg * (liquids.wgr + liquids.cgr) / 1e4 / 86400.0;
+
+    let local = |d: f64, md: f64, sin: f64, p: f64| -> Result<Local, CalcError> {
+        let t = path.t(md);
+        let z = pressure::z_checked(gas.method, gas.pc, gas.tc, t, p)?.value;
+        let area = PI * d * d / 4.0;
+        let rho_g = 3484.4 * p * gas.rg / (z * t);
+        let cond = Conditions {
+            p,
+            d,
+            ee,
+            friction,
+            sin,
+            rho_g,
+            mu_g: gas.mu(t, p, z) * 1e-3,
+            rho_l,
+            mu_l,
+            sigma,
+            vsg: qg / 86400.0 * P_SC * t * z / (p * T_SC) / area,
+            vsl: ql / area,
+        };
+        let (gradient, holdup, pattern) = match method {
+            MultiphaseMethod::Gray => cond.gray(),
+            MultiphaseMethod::BeggsBrill => cond.beggs_brill(),
+            MultiphaseMethod::HagedornBrown => cond.hagedorn_brown(),
+        };
+        Ok(Local {
+            gradient,
+            holdup,
+            density: rho_l * holdup + rho_g * (1.0 - holdup),
+            z,
+            pattern,
+        })
+    };
+    let point = |md: f64, p: f64, l: &Local| MultiphasePoint {
+        md,
+        tvd: path.tvd(md),
+        p,
+        t: path.t(md),
+        z: l.z,
+        holdup: l.holdup,
+        density: l.density,
+        gradient: l.gradient * 1e-4,
+        pattern: l.pattern,
+    };
+    let sin_between = |a: f64, b: f64| {
+        if b > a {
+            (path.tvd(b) - path.tvd(a)) / (b - a)
+        } else {
+            1.0
+        }
+    };
+
+    let mut p = pts;
+    let mut steps = 0;
+    let first = &sections[0];
+    let top = local(first.d, 0.0, sin_between(0.0, first.h.min(MAX_DL)), p)?;
+    let mut points = vec![point(0.0, p, &top)];
+    let mut md = 0.0;
+    for section in sections {
+        let end = md + section.h;
+        while md < end {
+            let next = (((md / step + 1e-9).floor() + 1.0) * step).min(end);
+            // 改进欧拉法（预估-校正）逐步推算
+            let n = (((next - md) / MAX_DL).ceil() as usize).max(1);
+            let dl = (next - md) / n as f64;
+            let mut last = None;
+            for k in 0..n {
+                let (l0, l1) = (md + dl * k as f64, md + dl * (k + 1) as f64);
+                let sin = sin_between(l0, l1);
+                let g0 = local(section.d, l0, sin, p)?.gradient * 1e-6;
+                let pred = pressure::check_pressure(p + g0 * dl)?;
+                let g1 = local(section.d, l1, sin, pred)?.gradient * 1e-6;
+                p = pressure::check_pressure(p + 0.5 * (g0 + g1) * dl)?;
+                last = Some(local(section.d, l1, sin, p)?);
+                steps += 1;
+            }
+            md = next;
+            if let Some(l) = last {
+                points.push(point(md, p, &l));
+            }
+        }
+    }
+
+    let sol = Solution {
+        value: p,
+        iterations: steps,
+        residual: 0.0,
+        converged: p.is_finite(),
+    };
+    Ok((points, pressure::check_converged(sol)?))
+}
+
+// 关联式所需的局部参数（SI 单位）
+struct Conditions {
+    p: f64,  // MPa
+    d: f64,  // 内径，m
+    ee: f64, // 绝对粗糙度，m
+    friction: FrictionMethod,
+    sin: f64,   // 井斜角余角的正弦（垂直为 1）
+    rho_g: f64, // kg/m³
+    mu_g: f64,  // Pa·s
+    rho_l: f64, // kg/m³
+    mu_l: f64,  // Pa·s
+    sigma: f64, // N/m
+    vsg: f64,   // 气相表观流速，m/s
+    vsl: f64,   // 液相表观流速，m/s
+}
+
+impl Conditions {
+    fn vm(&self) -> f64 {
+        self.vsg + self.vsl
+    }
+
+    // 无滑脱持液率
+    fn lambda(&self) -> f64 {
+        self.vsl / self.vm()
+    }
+
+    fn rho_ns(&self) -> f64 {
+        let l = self.lambda();
+        self.rho_l * l + self.rho_g * (1.0 - l)
+    }
+
+    fn mu_ns(&self) -> f64 {
+        let l = self.lambda();
+        self.mu_l * l + self.mu_g * (1.0 - l)
+    }
+
+    fn friction(&self, re: f64, roughness: f64) -> f64 {
+        self.friction.factor(re, roughness, self.d)
+    }
+
+    // 无产液时按单相气计算
+    fn gas_only(&self) -> (f64, f64, Option<&'static str>) {
+        let vm = self.vm();
+        let f = self.friction(
+            friction::reynolds(self.rho_g, vm, self.d, self.mu_g),
+            self.ee,
+        );
+        let dp = self.rho_g * G * self.sin + f * self.rho_g * vm * vm / (2.0 * self.d);
+        (dp, 0.0, None)
+    }
+
+    // Gray：HL = 1 - (1 - λ)(1 - e^A)
+    // A = -2.314·[Nv(1 + 205/ND)]^B，B = 0.0814·[1 - 0.0554·ln(1 + 730R/(R + 1))]
+    fn gray(&self) -> (f64, f64, Option<&'static str>) {
+        if self.vsl <= 0.0 {
+            return self.gas_only();
+        }
+        let (lambda, vm, rho_ns) = (self.lambda(), self.vm(), self.rho_ns());
+        let drho = self.rho_l - self.rho_g;
+        let r = self.vsl / self.vsg;
+        let nv = rho_ns.powi(2) * vm.powi(4) / (G * self.sigma * drho);
+        let nd = G * drho * self.d.powi(2) / self.sigma;
+        let b = 0.0814 * (1.0 - 0.0554 * (1.0 + 730.0 * r / (r + 1.0)).ln());
+        let a = -2.314 * (nv * (1.0 + 205.0 / nd)).powf(b);
+        let holdup = 1.0 - (1.0 - lambda) * (1.0 - a.exp());
+
+        // 液膜等效粗糙度
+        let k0 = 28.5 * self.sigma / (rho_ns * vm * vm);
+        let ke = if r >= 0.007 {
+            k0
+        } else {
+            self.ee + r * (k0 - self.ee) / 0.007
+        };
+        let ke = ke.max(8.4e-6);
+        let f = self.friction(friction::reynolds(rho_ns, vm, self.d, self.mu_ns()), ke);
+        let rho_s = self.rho_l * holdup + self.rho_g * (1.0 - holdup);
+        let dp = rho_s * G * self.sin + f * rho_ns * vm * vm / (2.0 * self.d);
+        (dp, holdup, None)
+    }
+
+    // Beggs-Brill：按流型计算水平持液率并做倾角校正，含 Payne 校正系数（上倾 0.924、下倾 0.685）
+    fn beggs_brill(&self) -> (f64, f64, Option<&'static str>) {
+        if self.vsl <= 0.0 {
+            return self.gas_only();
+        }
+        let (lambda, vm, rho_ns) = (self.lambda(), self.vm(), self.rho_ns());
+        let nfr = vm * vm / (G * self.d);
+        let nlv = self.vsl * (self.rho_l / (G * self.sigma)).powf(0.25);
+        let l1 = 316.0 * lambda.powf(0.302);
+        let l2 = 0.0009252 * lambda.powf(-2.4684);
+        let l3 = 0.10 * lambda.powf(-1.4516);
+        let l4 = 0.5 * lambda.powf(-6.738);
+
+        let sin = self.sin.clamp(-1.0, 1.0);
+        let angle = sin.asin();
+        let holdup_of = |(a, b, c): (f64, f64, f64), uphill: (f64, f64, f64, f64)| {
+            let h0 = (a * lambda.powf(b) / nfr.powf(c)).max(lambda);
+            let (e, f, g, h) = if angle < 0.0 { DOWN_C } else { uphill };
+            let cc =
+                ((1.0 - lambda) * (e * lambda.powf(f) * nlv.powf(g) * nfr.powf(h)).ln()).max(0.0);
+            let s = (1.8 * angle).sin();
+            let psi = 1.0 + cc * (s - s.powi(3) / 3.0);
+            let payne = if angle < 0.0 { 0.685 } else { 0.924 };
+            (h0 * psi * payne).clamp(lambda, 1.0)
+        };
+        const SEG: (f64, f64, f64) = (0.98, 0.4846, 0.0868);
+        const INT: (f64, f64, f64) = (0.845, 0.5351, 0.0173);
+        const DIS: (f64, f64, f64) = (1.065, 0.5824, 0.0609);
+        const SEG_C: (f64, f64, f64, f64) = (0.011, -3.768, 3.539, -1.614);
+        const INT_C: (f64, f64, f64, f64) = (2.96, 0.305, -0.4473, 0.0978);
+        const DIS_C: (f64, f64, f64, f64) = (1.0, 0.0, 0.0, 0.0);
+        const DOWN_C: (f64, f64, f64, f64) = (4.70, -0.3692, 0.1244, -0.5056);
+
+        let (holdup, pattern) = if (lambda < 0.01 && nfr < l1) || (lambda >= 0.01 && nfr < l2) {
+            (holdup_of(SEG, SEG_C), "分离流")
+        } else if lambda >= 0.01 && nfr <= l3 {
+            let a = (l3 - nfr) / (l3 - l2);
+            (
+                a * holdup_of(SEG, SEG_C) + (1.0 - a) * holdup_of(INT, INT_C),
+                "过渡流",
+            )
+        } else if (lambda < 0.4 && nfr < l1) || (lambda >= 0.4 && nfr <= l4) {
+            (holdup_of(INT, INT_C), "间歇流")
+        } else {
+            (holdup_of(DIS, DIS_C), "分散流")
+        };
+
+        // 两相摩阻系数 ftp = fn·e^s
+        let fns = self.friction(
+            friction::reynolds(rho_ns, vm, self.d, self.mu_ns()),
+            self.ee,
+        );
+        let y = lambda / holdup.powi(2);
+        let s = if y > 1.0 && y < 1.2 {
+            (2.2 * y - 1.2).ln()
+        } else {
+            let ln = y.ln();
+            ln / (-0.0523 + 3.182 * ln - 0.8725 * ln.powi(2) + 0.01853 * ln.powi(4))
+        };
+        let ftp = fns * s.exp();
+        let rho_s = self.rho_l * holdup + self.rho_g * (1.0 - holdup);
+        let dp = rho_s * G * sin + ftp * rho_ns * vm * vm / (2.0 * self.d);
+        (dp, holdup, Some(pattern))
+    }
+
+    // 修正 Hagedorn-Brown：持液率图版采用拟合公式，持液率不小于无滑脱持液率
+    fn hagedorn_brown(&self) -> (f64, f64, Option<&'static str>) {
+        if self.vsl <= 0.0 {
+            return self.gas_only();
+        }
+        let (lambda, vm, rho_ns) = (self.lambda(), self.vm(), self.rho_ns());
+        let k = (self.rho_l / (G * self.sigma)).powf(0.25);
+        let nlv = self.vsl * k;
+        let ngv = self.vsg * k;
+        let nd = self.d * (self.rho_l * G / self.sigma).sqrt();
+        let nl = self.mu_l * (G / (self.rho_l * self.sigma.powi(3))).powf(0.25);
+
+        // 粘度数 CNL
+        let x1 = nl.log10() + 3.0;
+        let cnl = 10f64.powf(
+            -2.69851 + 0.15841 * x1 - 0.551 * x1.powi(2) + 0.54785 * x1.powi(3)
+                - 0.12195 * x1.powi(4),
+        );
+        // HL/ψ
+        let x = nlv / ngv.powf(0.575) * (self.p / P_SC).powf(0.1) * cnl / nd;
+        let hl_psi = ((0.0047 + 1123.32 * x + 729489.64 * x * x)
+            / (1.0 + 1097.1566 * x + 722153.97 * x * x))
+            .sqrt();
+        // 校正系数 ψ
+        let x2 = ngv * nl.powf(0.38) / nd.powf(2.14);
+        let psi = if x2 <= 0.01 {
+            1.0
+        } else {
+            ((1.0886 - 69.9473 * x2 + 2334.3497 * x2.powi(2) - 12896.683 * x2.powi(3))
+                / (1.0 - 53.4401 * x2 + 1517.9369 * x2.powi(2) - 8419.8115 * x2.powi(3)))
+            .max(1.0)
+        };
+        let holdup = (hl_psi * psi).clamp(lambda, 1.0);
+
+        let rho_s = self.rho_l * holdup + self.rho_g * (1.0 - holdup);
+        let mu_s = self.mu_l.powf(holdup) * self.mu_g.powf(1.0 - holdup);
+        let f = self.friction(friction::reynolds(rho_ns, vm, self.d, mu_s), self.ee);
+        let dp = rho_s * G * self.sin + f * rho_ns.powi(2) * vm * vm / (2.0 * rho_s * self.d);
+        (dp, holdup, None)
+    }
+}
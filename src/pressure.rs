@@ -1,6 +1,97 @@
-//Dranchuk,Purris和Robinson法计算z
+use crate::friction::{self, FrictionMethod};
+use crate::handlers::calculate_viscosity;
+use crate::solver::{Newton, Solution};
+use crate::temperature::{Profile, TemperatureModel};
+use crate::trajectory::Trajectory;
+use crate::zfactor::ZMethod;
+use serde::{Deserialize, Serialize};
+
+// 压力迭代收敛容差，MPa
+const PRESSURE_TOL: f64 = 1e-8;
+
+/// 计算错误
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CalcError {
+    /// 压力不大于 0，MPa
+    NonPositivePressure(f64),
+    /// 对比温度低于关联式适用下限
+    TprBelowRange { tpr: f64, min: f64 },
+    /// 摩尔分数超出 [0, 1] 或非烃组分之和大于 1
+    InvalidMoleFraction(f64),
+    /// 迭代未收敛
+    NotConverged { iterations: u32, residual: f64 },
+    /// 其他输入参数无效
+    InvalidInput(&'static str),
+}
+
+impl CalcError {
+    /// 错误代码（返回给前端）
+    pub fn code(&self) -> &'static str {
+        match self {
+            CalcError::NonPositivePressure(_) => "non_positive_pressure",
+            CalcError::TprBelowRange { .. } => "tpr_below_range",
+            CalcError::InvalidMoleFraction(_) => "invalid_mole_fraction",
+            CalcError::NotConverged { .. } => "not_converged",
+            CalcError::InvalidInput(_) => "invalid_input",
+        }
+    }
+}
+
+impl std::fmt::Display for CalcError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CalcError::NonPositivePressure(p) => write!(f, "压力必须大于 0（p = {p} MPa）"),
+            CalcError::TprBelowRange { tpr, min } => {
+                write!(f, "对比温度 Tpr = {tpr:.4} 低于关联式适用下限 {min}")
+            }
+            CalcError::InvalidMoleFraction(y) => {
+                write!(f, "摩尔分数须在 0～1 之间且 N2+CO2+H2S 不大于 1（{y}）")
+            }
+            CalcError::NotConverged {
+                iterations,
+                residual,
+            } => write!(f, "迭代 {iterations} 次未收敛（残差 {residual:e}）"),
+            CalcError::InvalidInput(msg) => write!(f, "{msg}"),
+        }
+    }
+}
+
+impl std::error::Error for CalcError {}
+
+/// 校验压力大于 0
+pub fn check_pressure(p: f64) -> Result<f64, CalcError> {
+    if p > 0.0 {
+        Ok(p)
+    } else {
+        Err(CalcError::NonPositivePressure(p))
+    }
+}
+
+/// 校验 N2、CO2、H2S 摩尔分数
+pub fn check_fractions(yn2: f64, yco2: f64, yh2s: f64) -> Result<(), CalcError> {
+    for y in [yn2, yco2, yh2s, yn2 + yco2 + yh2s] {
+        if !(0.0..=1.0).contains(&y) {
+            return Err(CalcError::InvalidMoleFraction(y));
+        }
+    }
+    Ok(())
+}
+
+/// 迭代结果须已收敛
+pub fn check_converged(sol: Solution) -> Result<Solution, CalcError> {
+    if sol.converged {
+        Ok(sol)
+    } else {
+        Err(CalcError::NotConverged {
+            iterations: sol.iterations,
+            residual: sol.residual,
+        })
+    }
+}
+
+//计算偏差因子z，method 指定所用关联式（默认 Dranchuk,Purris和Robinson法）
 //pc临界压力  tc临界温度  t井底温度  p压力
-pub fn z<Pc, Tc, T, P>(pc: Pc, tc: Tc, t: T, p: P) -> f64
+pub fn z<Pc, Tc, T, P>(method: ZMethod, pc: Pc, tc: Tc, t: T, p: P) -> f64
 where
     Pc: Into<f64>,
     Tc: Into<f64>,
@@ -12,63 +103,32 @@ where
     let t = t.into();
     let p = p.into();
 
-    const A1: f64 = 0.31506237;
-    const A2: f64 = -1.0467099;
-    const A3: f64 = -0.57832729;
-    const A4: f64 = 0.53530771;
-    const A5: f64 = -0.61232032;
-    const A6: f64 = -0.10488813;
-    const A7: f64 = 0.68157001;
-    const A8: f64 = 0.68446549;
+    z_solve(method, pc, tc, t, p).value
+}
 
+//计算偏差因子z，同时返回迭代次数、残差和收敛标志
+pub fn z_solve(method: ZMethod, pc: f64, tc: f64, t: f64, p: f64) -> Solution {
     let ppr = p / pc;
     let tpr = t / tc;
+    method.correlation().solve(ppr, tpr)
+}
 
-    if ppr >= 0.1 && ppr <= 14.0 {
-        let mut luopr = 0.27 * ppr / tpr;
-        for _ in 0..25 {
-            let fl = luopr - (0.27 * ppr) / tpr
-                + (A1 + A2 / tpr + A3 / tpr.powi(3)) * luopr.powi(2)
-                + (A4 + A5 / tpr) * luopr.powi(3)
-                + (A5 * A6 * luopr.powi(6)) / tpr
-                + (A7 * luopr.powi(3) / tpr.powi(3))
-                    * (1.0 + A8 * luopr.powi(2))
-                    * (-A8 * luopr.powi(2)).exp();
-
-            let dfl = 1.0
-                + (A1 + A2 / tpr + A3 / tpr.powi(3)) * (2.0 * luopr)
-                + (A4 + A5 / tpr) * (3.0 * luopr.powi(2))
-                + (A5 * A6 / tpr) * (6.0 * luopr.powi(5))
-                + (A7 / tpr.powi(3)) * (3.0 * luopr.powi(2) + A8 * (3.0 * luopr.powi(4)))
-                - A8.powi(2) * (2.0 * luopr.powi(6)) * (-A8 * luopr.powi(2)).exp();
-
-            luopr -= fl / dfl;
-        }
-        0.27 * ppr / (luopr * tpr)
-    } else {
-        let tt = 1.0 / tpr;
-        let mut yy = 0.06125 * ppr * tt * (-1.2 * (1.0 - tt).powi(2)).exp();
-        for _ in 0..25 {
-            let fh = -0.06125 * ppr * tt * (-1.2 * (1.0 - tt).powi(2)).exp()
-                + (yy + yy.powi(2) + yy.powi(3) - yy.powi(4)) / (1.0 - yy).powi(3)
-                - (14.76 * tt - 9.76 * tt.powi(2) + 4.58 * tt.powi(3)) * yy.powi(2)
-                + (90.7 * tt - 242.2 * tt.powi(2) + 42.4 * tt.powi(3)) * yy.powf(2.18 + 2.82 * tt);
-
-            let dh = (1.0 + 4.0 * yy + 4.0 * yy.powi(2) - 4.0 * yy.powi(3) + yy.powi(4))
-                / (1.0 - yy).powi(4)
-                - (29.52 * tt - 19.52 * tt.powi(2) + 9.16 * tt.powi(3)) * yy
-                + (2.18 + 2.82 * tt)
-                    * (90.7 * tt - 242.2 * tt.powi(2) + 42.4 * tt.powi(3))
-                    * yy.powf(1.18 + 2.82 * tt);
-
-            yy -= fh / dh;
-        }
-        0.06125 * ppr * tt * (-1.2 * (1.0 - tt).powi(2)).exp() / yy
+//计算偏差因子z，校验压力、对比温度适用范围和收敛性
+pub fn z_checked(method: ZMethod, pc: f64, tc: f64, t: f64, p: f64) -> Result<Solution, CalcError> {
+    check_pressure(p)?;
+    let correlation = method.correlation();
+    let tpr = t / tc;
+    if tpr.is_nan() || tpr < correlation.min_tpr() {
+        return Err(CalcError::TprBelowRange {
+            tpr,
+            min: correlation.min_tpr(),
+        });
     }
+    check_converged(correlation.solve(p / pc, tpr))
 }
 
-//计算天然气体积系数
-pub fn bg<Pc, Tc, T, P>(pc: Pc, tc: Tc, t: T, p: P) -> f64
+//计算天然气体积系数，同时返回 Z 迭代的诊断信息
+pub fn bg<Pc, Tc, T, P>(method: ZMethod, pc: Pc, tc: Tc, t: T, p: P) -> Solution
 where
     Pc: Into<f64>,
     Tc: Into<f64>,
@@ -79,14 +139,17 @@ where
     let tc = tc.into();
     let t = t.into();
     let p = p.into();
-    0.0003447 * z(pc, tc, t, p) * t / p
+    z_solve(method, pc, tc, t, p).map(|z| 0.0003447 * z * t / p)
 }
 
 //计算粘度μ
 // 'Lee,Gonzalez和Eakin法计算粘度μ,单位cp
 // '杨继盛“采气工艺基础”（旧）40页
 // '酸性气体修正（1986年）
+// 同时返回 Z 迭代的诊断信息
+#[allow(clippy::too_many_arguments)]
 pub fn niandu<Rg, Pc, Tc, T, P, Yn2, Yco2, Yh2s>(
+    method: ZMethod,
     rg: Rg,
     pc: Pc,
     tc: Tc,
@@ -95,7 +158,7 @@ pub fn niandu<Rg, Pc, Tc, T, P, Yn2, Yco2, Yh2s>(
     yn2: Yn2,
     yco2: Yco2,
     yh2s: Yh2s,
-) -> f64
+) -> Solution
 where
     Rg: Into<f64>,
     Pc: Into<f64>,
@@ -126,16 +189,16 @@ where
     let x = 3.5 + 986.0 / (9.0 * t / 5.0) + 0.01 * 28.97 * rg;
     let y = 2.4 - 0.2 * x;
 
-    let z = z(pc, tc, t, p);
-    let density = 3.4844 * p * rg / (z * t);
+    z_solve(method, pc, tc, t, p).map(|z| {
+        let density = 3.4844 * p * rg / (z * t);
 
-    let llluopr = density; // 密度
-    let niandu = k * (x * llluopr.powf(y)).exp(); //niandu----粘度
-    niandu
+        let llluopr = density; // 密度
+        k * (x * llluopr.powf(y)).exp() //niandu----粘度
+    })
 }
 
-//计算天然气压缩系数
-pub fn cg<Pc, Tc, T, P>(pc: Pc, tc: Tc, t: T, p: P) -> f64
+//计算天然气压缩系数，同时返回该压力下 Z 迭代的诊断信息
+pub fn cg<Pc, Tc, T, P>(method: ZMethod, pc: Pc, tc: Tc, t: T, p: P) -> Solution
 where
     Pc: Into<f64>,
     Tc: Into<f64>,
@@ -146,60 +209,18 @@ where
     let tc: f64 = tc.into();
     let t: f64 = t.into();
     let p: f64 = p.into();
-    // 定义常数
-    let a1 = 0.31506237;
-    let a2 = -1.0467099;
-    let a3 = -0.57832729;
-    let a4 = 0.53530771;
-    let a5 = -0.61232032;
-    let a6 = -0.10488813;
-    let a7 = 0.68157001;
-    let a8 = 0.68446549;
 
     // 计算无量纲压力和温度
     let ppr = p / pc;
     let tpr = t / tc;
 
-    // 初始化luopr
-    let mut luopr = 0.27 * ppr / tpr;
-
-    // 使用牛顿迭代法求解luopr
-    for _ in 0..30 {
-        let fluopr = luopr - (0.27 * ppr / tpr)
-            + (a1 + a2 / tpr + a3 / tpr.powi(3)) * luopr.powi(2)
-            + (a4 + a5 / tpr) * luopr.powi(3)
-            + (a5 * a6 * luopr.powi(6) / tpr)
-            + (a7 * luopr.powi(3) / tpr.powi(3))
-                * (1.0 + a8 * luopr.powi(2))
-                * (-a8 * luopr.powi(2)).exp();
-        let dfluopr = 1.0
-            + (a1 + a2 / tpr + a3 / tpr.powi(3)) * (2.0 * luopr)
-            + (a4 + a5 / tpr) * (3.0 * luopr.powi(2))
-            + (a5 * a6 / tpr) * (6.0 * luopr.powi(5))
-            + (a7 / tpr.powi(3)) * (3.0 * luopr.powi(2) + a8 * (3.0 * luopr.powi(4)))
-            - a8.powi(2) * (2.0 * luopr.powi(6)) * (-a8 * luopr.powi(2)).exp();
-        luopr = luopr - fluopr / dfluopr;
-    }
-
-    // 计算压缩系数z
-    let z = 0.27 * ppr / (luopr * tpr);
-
-    // 计算dzlt
-    let dzlt = (a1 + a2 / tpr + a3 / tpr.powi(3))
-        + 2.0 * (a4 + a5 / tpr) * luopr
-        + 5.0 * a5 * a6 * luopr.powi(4) / tpr
-        + (2.0 * a7 * luopr / tpr.powi(3))
-            * (1.0 + a8 * luopr.powi(2) - a8.powi(2) * luopr.powi(4))
-            * (-a8 * luopr.powi(2)).exp();
-
-    // 计算cpr
-    let cpr = 1.0 / ppr - (0.27 / (z.powi(2) * tpr)) * (dzlt / (1.0 + luopr * dzlt / z));
-
-    // 返回压缩系数cg
-    cpr / pc
+    // 计算cpr后换算为压缩系数cg
+    let cpr = method.correlation().cpr(ppr, tpr);
+    z_solve(method, pc, tc, t, p).map(|_| cpr / pc)
 }
 
-pub fn density<Rg, Pc, T, P>(rg: Rg, pc: Pc, tc: Rg, t: T, p: P) -> f64
+//计算天然气密度，同时返回 Z 迭代的诊断信息
+pub fn density<Rg, Pc, T, P>(method: ZMethod, rg: Rg, pc: Pc, tc: Rg, t: T, p: P) -> Solution
 where
     Pc: Into<f64>,
     Rg: Into<f64>,
@@ -215,33 +236,45 @@ where
     // 'Lee,Gonzalez和Eakin法计算密度ρ
     // '杨继盛“采气工艺基础”（旧）40页
     // 'Dranchuk,Purris和Robinson法计算z
-    let zz = z(pc, tc, t, p);
-
-    let lluopr = 3.4844 * p * rg / (zz * t);
-    lluopr //'lluopr----密度
+    z_solve(method, pc, tc.into(), t, p).map(|zz| 3.4844 * p * rg / (zz * t)) //'lluopr----密度
 }
 //平均温度和平均压缩系数计算法计算井底压力(静气柱）
 //pws:pressure wellbore shut-in 关井状态下的井筒压力
 //pts:pressure at tubing surface 井口压力
 //tts:tubing temperature at surface"，即井口管柱温度。
 //tws:tubing temperature at some specific depth"，即井筒温度或特定深度的管柱温度。
-pub fn pws(rg: f64, pc: f64, tc: f64, h: f64, tts: f64, tws: f64, pts: f64) -> f64 {
-    let mut pws = pts + pts * h / 12192.0; // 给定一个初值
-    for _ in 0..100 {
-        let p = (pts + pws) / 2.0; //平均井筒压力
-        let t = (tts + tws) / 2.0; //平均井筒温度
-        let zz = z(pc, tc, t, p); //调用函数计算压缩系数z
-        pws = pts * ((0.03415 * rg * h) / (zz * t)).exp(); // 更新pws
+#[allow(clippy::too_many_arguments)]
+pub fn pws(
+    method: ZMethod,
+    rg: f64,
+    pc: f64,
+    tc: f64,
+    h: f64,
+    tts: f64,
+    tws: f64,
+    pts: f64,
+) -> Solution {
+    let t = (tts + tws) / 2.0; //平均井筒温度
+    let pws0 = pts + pts * h / 12192.0; // 给定一个初值
+
+    // 残差 f(pws) = pws - pts·exp(0.03415·rg·h/(z·t))，z 取平均井筒压力下的值
+    let sol = Newton::new().tol(PRESSURE_TOL).solve(
+        |pws| {
+            let p = (pts + pws) / 2.0; //平均井筒压力
+            let zz = z(method, pc, tc, t, p); //调用函数计算压缩系数z
+            pws - pts * ((0.03415 * rg * h) / (zz * t)).exp()
+        },
+        pws0,
+    );
+
+    // 最终平均压力下 Z 的迭代也须收敛
+    let z_sol = z_solve(method, pc, tc, t, (pts + sol.value) / 2.0);
+    Solution {
+        converged: sol.converged && z_sol.converged,
+        ..sol
     }
-    return pws;
 }
 
-//计算油管采气时的井底流动压力Pwf(利用平均温度和平均压缩系数)
-//yn2:N2摩尔分数  yco2:CO2摩尔分数  yh2s:H2S摩尔分数  d1:油管直径，单位：m  ee:粗糙系数
-//d1、h1---第1段油管直径和下入长度
-//d2、h2---第2段油管直径和下入长度
-//d3、h3---产层直径和油管底部至中部井深的长度
-
 ///平均温度和平均压缩系数计算法计算井口压力(静气柱）well pressure(bottom shutdown)
 /// 静气柱井底压力（平均温度/平均压缩系数法）
 /// 参数:
@@ -253,8 +286,10 @@ pub fn pws(rg: f64, pc: f64, tc: f64, h: f64, tts: f64, tws: f64, pts: f64) -> f
 ///   tws  – 井底静温，℃
 ///   pts  – 井口静压，MPa
 /// 返回:
-///   井底静压，MPa
+///   井底静压，MPa（含迭代诊断信息）
+#[allow(clippy::too_many_arguments)]
 pub fn pwbs<Rg, Pc, Tc, H, Tts, Tws, Pts>(
+    method: ZMethod,
     rg: Rg,
     pc: Pc,
     tc: Tc,
@@ -262,7 +297,7 @@ pub fn pwbs<Rg, Pc, Tc, H, Tts, Tws, Pts>(
     tts: Tts,
     tws: Tws,
     pts: Pts,
-) -> f64
+) -> Solution
 where
     Rg: Into<f64>,
     Pc: Into<f64>,
@@ -272,26 +307,16 @@ where
     Tws: Into<f64>,
     Pts: Into<f64>,
 {
-    let rg: f64 = rg.into();
-    let pc: f64 = pc.into();
-    let tc: f64 = tc.into();
-    let h: f64 = h.into();
-    let tts: f64 = tts.into();
-    let tws: f64 = tws.into();
-    let pts: f64 = pts.into();
-
-    // 初始估值：线性外推
-    let mut pws = pts + pts * h / 12192.0;
-
-    // 固定 30 次迭代，与 VBA 保持一致
-    for _ in 0..30 {
-        let p = (pts + pws) * 0.5;
-        let t = (tts + tws) * 0.5;
-
-        let zz = z(pc, tc, t, p); // 已实现的压缩因子函数
-        pws = pts * (0.03415 * rg * h / (zz * t)).exp();
-    }
-    pws
+    pws(
+        method,
+        rg.into(),
+        pc.into(),
+        tc.into(),
+        h.into(),
+        tts.into(),
+        tws.into(),
+        pts.into(),
+    )
 }
 
 /// 计算井口压力（反向计算）
@@ -305,8 +330,10 @@ where
 ///   tws  – 井底静温，℃
 ///   pwbs – 井底静压，MPa
 /// 返回:
-///   井口静压，MPa
+///   井口静压，MPa（含迭代诊断信息）
+#[allow(clippy::too_many_arguments)]
 pub fn ph<Rg, Pc, Tc, H, Tts, Tws, Pwbs>(
+    method: ZMethod,
     rg: Rg,
     pc: Pc,
     tc: Tc,
@@ -314,7 +341,7 @@ pub fn ph<Rg, Pc, Tc, H, Tts, Tws, Pwbs>(
     tts: Tts,
     tws: Tws,
     pwbs: Pwbs,
-) -> f64
+) -> Solution
 where
     Rg: Into<f64>,
     Pc: Into<f64>,
@@ -332,22 +359,533 @@ where
     let tws: f64 = tws.into();
     let pwbs: f64 = pwbs.into();
 
+    let t_avg = (tts + tws) * 0.5;
+
     // 使用牛顿迭代法反向计算井口压力
-    let mut pts = pwbs / (1.0 + h / 12192.0); // 初始估值
-
-    for _ in 0..30 {
-        let p_avg = (pts + pwbs) * 0.5;
-        let t_avg = (tts + tws) * 0.5;
-        let zz = z(pc, tc, t_avg, p_avg);
-
-        // 根据井底压力公式反向推导
-        let pts_new = pwbs / (0.03415 * rg * h / (zz * t_avg)).exp();
-        if (pts_new - pts).abs() < 0.0001 {
-            pts = pts_new;
-            break;
+    // 残差 f(pts) = pts - pwbs/exp(0.03415·rg·h/(z·t))
+    let sol = Newton::new().tol(PRESSURE_TOL).solve(
+        |pts| {
+            let p_avg = (pts + pwbs) * 0.5;
+            let zz = z(method, pc, tc, t_avg, p_avg);
+            pts - pwbs / (0.03415 * rg * h / (zz * t_avg)).exp()
+        },
+        pwbs / (1.0 + h / 12192.0), // 初始估值
+    );
+
+    let z_sol = z_solve(method, pc, tc, t_avg, (sol.value + pwbs) * 0.5);
+    Solution {
+        converged: sol.converged && z_sol.converged,
+        ..sol
+    }
+}
+
+/// 静气柱井底压力（Cullender-Smith 法）
+/// 参数同 `pws`（h 为垂深），井温沿井深线性分布；深井、高压井精度高于平均温度/平均压缩系数法
+#[allow(clippy::too_many_arguments)]
+pub fn pws_cullender_smith(
+    method: ZMethod,
+    rg: f64,
+    pc: f64,
+    tc: f64,
+    h: f64,
+    tts: f64,
+    tws: f64,
+    pts: f64,
+) -> Result<Solution, CalcError> {
+    check_pressure(pts)?;
+    if h < 0.0 {
+        return Err(CalcError::InvalidInput("井深不能为负"));
+    }
+    // 静气柱无摩阻，管径与非烃组分不参与计算
+    let gas = GasProps {
+        method,
+        rg,
+        pc,
+        tc,
+        yn2: 0.0,
+        yco2: 0.0,
+        yh2s: 0.0,
+    };
+    let column = [TubingSection { d: 1.0, h }];
+    let vertical = Trajectory::vertical();
+    let path = Path::new(
+        &column,
+        &vertical,
+        &TemperatureModel::Linear,
+        tts,
+        tws,
+        rg,
+        0.0,
+    );
+    let flow = Flow {
+        gas: &gas,
+        d: 1.0,
+        ee: 0.0,
+        friction: FrictionMethod::default(),
+        qg: 0.0,
+    };
+    cullender_smith(&flow, &path, 0.0, h, pts)
+}
+
+/// 按指定方法计算静气柱井底压力
+/// 平均温度/平均压缩系数法不校验收敛，由调用方根据 `converged` 处理
+#[allow(clippy::too_many_arguments)]
+pub fn pws_by(
+    bhp: BhpMethod,
+    method: ZMethod,
+    rg: f64,
+    pc: f64,
+    tc: f64,
+    h: f64,
+    tts: f64,
+    tws: f64,
+    pts: f64,
+) -> Result<Solution, CalcError> {
+    match bhp {
+        BhpMethod::AverageTz => Ok(pwbs(method, rg, pc, tc, h, tts, tws, pts)),
+        BhpMethod::CullenderSmith => pws_cullender_smith(method, rg, pc, tc, h, tts, tws, pts),
+    }
+}
+
+// ============ 井底流动压力 ============
+// 积分步长上限（Cullender-Smith），m
+const MAX_DH: f64 = 100.0;
+// 摩阻项系数：p MPa，qg m³/d（20 ℃、0.101325 MPa），d m
+const FRICTION_C: f64 = 1.324e-18;
+
+/// 流动计算所需的气体参数
+#[derive(Debug, Clone, Copy)]
+pub struct GasProps {
+    pub method: ZMethod,
+    pub rg: f64,
+    pub pc: f64, // MPa
+    pub tc: f64, // K
+    pub yn2: f64,
+    pub yco2: f64,
+    pub yh2s: f64,
+}
+
+impl GasProps {
+    /// 粘度，mPa·s
+    pub fn mu(&self, t: f64, p: f64, z: f64) -> f64 {
+        let density = 3.4844 * p * self.rg / (z * t);
+        calculate_viscosity(self.rg, t, density, self.yn2, self.yco2, self.yh2s)
+    }
+
+    /// 内径 d（m）的管内以产气量 qg（m³/d）流动时的雷诺数
+    pub fn reynolds(&self, t: f64, p: f64, z: f64, qg: f64, d: f64) -> f64 {
+        friction::gas_reynolds(qg, self.rg, d, self.mu(t, p, z))
+    }
+}
+
+/// 管柱分段（自井口向下）
+/// d1、h1---第1段油管直径和下入长度
+/// d2、h2---第2段油管直径和下入长度
+/// d3、h3---产层直径和油管底部至中部井深的长度
+#[derive(Deserialize, Debug, Clone, Copy)]
+pub struct TubingSection {
+    pub d: f64, // 内径，m
+    pub h: f64, // 长度，m
+}
+
+/// 井底压力计算方法（流压与静压通用）
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum BhpMethod {
+    /// Cullender-Smith 分步积分（井底流压默认）
+    #[default]
+    CullenderSmith,
+    /// 平均温度和平均压缩系数法（井底静压默认）
+    AverageTz,
+}
+
+impl BhpMethod {
+    pub fn name(self) -> &'static str {
+        match self {
+            BhpMethod::CullenderSmith => "Cullender-Smith",
+            BhpMethod::AverageTz => "平均温度平均偏差系数法",
+        }
+    }
+}
+
+/// 计算油管采气时的井底流动压力 Pwf
+/// 斜井按井眼轨迹换算：重力项用垂深，摩阻项用测深
+/// 参数:
+///   gas         – 气体参数
+///   method      – 计算方法
+///   sections    – 管柱分段（长度为测深），自井口向下
+///   trajectory  – 井眼轨迹，直井取 `Trajectory::vertical()`
+///   temperature – 井筒温度模型
+///   ee          – 绝对粗糙度，m
+///   friction    – 摩阻系数计算方法
+///   tts         – 井口流温（linear）或地表恒温层温度，K
+///   tws         – 井底流温，K
+///   pts         – 井口流压，MPa
+///   qg          – 产气量，m³/d（标准状况）
+/// 返回:
+///   井底流压，MPa（含各段迭代诊断信息：迭代次数累加，残差取最大值）
+#[allow(clippy::too_many_arguments)]
+pub fn pwf(
+    gas: &GasProps,
+    method: BhpMethod,
+    sections: &[TubingSection],
+    trajectory: &Trajectory,
+    temperature: &TemperatureModel,
+    ee: f64,
+    friction: FrictionMethod,
+    tts: f64,
+    tws: f64,
+    pts: f64,
+    qg: f64,
+) -> Result<Solution, CalcError> {
+    check_pressure(pts)?;
+    check_flow(sections, qg)?;
+    let path = Path::new(sections, trajectory, temperature, tts, tws, gas.rg, qg);
+    bhp_along(gas, method, sections, &path, ee, friction, pts, qg)
+}
+
+/// 与产气量 qg 下流动时相同井温剖面的静气柱井底压力
+/// 井底流压与之相减即为摩阻压降，不含流动井温剖面引起的气柱重力差
+/// 参数同 `pwf`
+#[allow(clippy::too_many_arguments)]
+pub fn static_bhp(
+    gas: &GasProps,
+    method: BhpMethod,
+    sections: &[TubingSection],
+    trajectory: &Trajectory,
+    temperature: &TemperatureModel,
+    ee: f64,
+    friction: FrictionMethod,
+    tts: f64,
+    tws: f64,
+    pts: f64,
+    qg: f64,
+) -> Result<Solution, CalcError> {
+    check_pressure(pts)?;
+    check_flow(sections, qg)?;
+    let path = Path::new(sections, trajectory, temperature, tts, tws, gas.rg, qg);
+    bhp_along(gas, method, sections, &path, ee, friction, pts, 0.0)
+}
+
+// 沿给定井温剖面逐段计算井底压力
+#[allow(clippy::too_many_arguments)]
+fn bhp_along(
+    gas: &GasProps,
+    method: BhpMethod,
+    sections: &[TubingSection],
+    path: &Path,
+    ee: f64,
+    friction: FrictionMethod,
+    pts: f64,
+    qg: f64,
+) -> Result<Solution, CalcError> {
+    let mut result = Solution::exact(pts);
+    let mut md = 0.0;
+    for section in sections {
+        let flow = Flow {
+            gas,
+            d: section.d,
+            ee,
+            friction,
+            qg,
+        };
+        let (md_in, md_out) = (md, md + section.h);
+        let sol = match method {
+            BhpMethod::CullenderSmith => cullender_smith(&flow, path, md_in, md_out, result.value)?,
+            BhpMethod::AverageTz => average_tz(&flow, path, md_in, md_out, result.value)?,
+        };
+        result = Solution {
+            value: sol.value,
+            iterations: result.iterations + sol.iterations,
+            residual: result.residual.abs().max(sol.residual.abs()),
+            converged: result.converged && sol.converged,
+        };
+        md = md_out;
+    }
+    check_converged(result)
+}
+
+/// 井筒压力剖面中的一个测点
+#[derive(Serialize, Debug, Clone, Copy)]
+pub struct TraversePoint {
+    pub md: f64,       // 测深，m
+    pub tvd: f64,      // 垂深，m
+    pub p: f64,        // 压力，MPa
+    pub t: f64,        // 温度，K
+    pub z: f64,        //
+    pub density: f64,  // 密度，g/cm³
+    pub gradient: f64, // 沿井筒的压力梯度，MPa/100m
+}
+
+/// 自井口向下按固定测深步长计算压力、温度剖面（Cullender-Smith 法）
+/// qg = 0 时为静气柱剖面；各分段交界处及井底均输出测点
+/// 参数:
+///   gas         – 气体参数
+///   sections    – 管柱分段（长度为测深），自井口向下
+///   trajectory  – 井眼轨迹，直井取 `Trajectory::vertical()`
+///   temperature – 井筒温度模型
+///   ee          – 绝对粗糙度，m
+///   friction    – 摩阻系数计算方法
+///   tts         – 井口温度（linear）或地表恒温层温度，K
+///   tws         – 井底温度，K
+///   pts         – 井口压力，MPa
+///   qg          – 产气量，m³/d（标准状况）
+///   step        – 测点间距，m
+/// 返回:
+///   各测点参数及迭代诊断信息（value 为井底压力）
+#[allow(clippy::too_many_arguments)]
+pub fn traverse(
+    gas: &GasProps,
+    sections: &[TubingSection],
+    trajectory: &Trajectory,
+    temperature: &TemperatureModel,
+    ee: f64,
+    friction: FrictionMethod,
+    tts: f64,
+    tws: f64,
+    pts: f64,
+    qg: f64,
+    step: f64,
+) -> Result<(Vec<TraversePoint>, Solution), CalcError> {
+    check_pressure(pts)?;
+    check_flow(sections, qg)?;
+    if step <= 0.0 {
+        return Err(CalcError::InvalidInput("测点间距须大于 0"));
+    }
+    let path = Path::new(sections, trajectory, temperature, tts, tws, gas.rg, qg);
+
+    let station = |flow: &Flow, md: f64, p: f64| -> Result<_, CalcError> {
+        let t = path.t(md);
+        let z = z_checked(gas.method, gas.pc, gas.tc, t, p)?.value;
+        let x = p / (t * z);
+        let f = flow.friction(t, p, z);
+        Ok(TraversePoint {
+            md,
+            tvd: path.tvd(md),
+            p,
+            t,
+            z,
+            density: 3.4844 * p * gas.rg / (z * t),
+            gradient: (0.03415 * gas.rg * x * path.cos(md) + f / x) * 100.0,
+        })
+    };
+
+    let flow_in = |section: &TubingSection| Flow {
+        gas,
+        d: section.d,
+        ee,
+        friction,
+        qg,
+    };
+    let mut points = vec![station(&flow_in(&sections[0]), 0.0, pts)?];
+    let mut result = Solution::exact(pts);
+    let mut md = 0.0;
+    for section in sections {
+        let flow = flow_in(section);
+        let end = md + section.h;
+        while md < end {
+            // 测点取 step 的整数倍测深，分段交界处补充测点
+            let next = (((md / step + 1e-9).floor() + 1.0) * step).min(end);
+            let sol = cullender_smith(&flow, &path, md, next, result.value)?;
+            result = Solution {
+                value: sol.value,
+                iterations: result.iterations + sol.iterations,
+                residual: result.residual.abs().max(sol.residual.abs()),
+                converged: result.converged && sol.converged,
+            };
+            md = next;
+            points.push(station(&flow, md, result.value)?);
         }
-        pts = pts_new;
     }
+    Ok((points, check_converged(result)?))
+}
+
+// 校验管柱分段和产气量
+pub(crate) fn check_flow(sections: &[TubingSection], qg: f64) -> Result<(), CalcError> {
+    if qg < 0.0 {
+        return Err(CalcError::InvalidInput("产气量不能为负"));
+    }
+    if sections.is_empty() || sections.iter().any(|s| !(s.d > 0.0 && s.h >= 0.0)) {
+        return Err(CalcError::InvalidInput(
+            "管柱分段的内径须大于 0、长度不能为负",
+        ));
+    }
+    Ok(())
+}
+
+// 某一管段内的流动条件
+struct Flow<'a> {
+    gas: &'a GasProps,
+    d: f64,  // 内径，m
+    ee: f64, // 绝对粗糙度，m
+    friction: FrictionMethod,
+    qg: f64, // 产气量，m³/d
+}
+
+impl Flow<'_> {
+    // 摩阻项 F，dp/dL = 0.03415·rg·(p/TZ)·cosθ + F·TZ/p（L 为测深）
+    fn friction(&self, t: f64, p: f64, z: f64) -> f64 {
+        if self.qg == 0.0 {
+            return 0.0;
+        }
+        let (gas, d) = (self.gas, self.d);
+        let re = gas.reynolds(t, p, z, self.qg, d);
+        0.03415 * gas.rg * FRICTION_C * self.friction.factor(re, self.ee, d) * self.qg.powi(2)
+            / d.powi(5)
+    }
+}
+
+// 井筒路径：测深与垂深换算及井温分布
+pub(crate) struct Path<'a> {
+    trajectory: &'a Trajectory,
+    md_total: f64,
+    profile: Profile,
+}
+
+impl<'a> Path<'a> {
+    pub(crate) fn new(
+        sections: &[TubingSection],
+        trajectory: &'a Trajectory,
+        temperature: &TemperatureModel,
+        tts: f64,
+        tws: f64,
+        rg: f64,
+        qg: f64,
+    ) -> Self {
+        let md_total: f64 = sections.iter().map(|s| s.h).sum();
+        let tvd_total = trajectory.tvd(md_total);
+        Path {
+            trajectory,
+            md_total,
+            profile: Profile::new(temperature, tts, tws, md_total, tvd_total, rg, qg),
+        }
+    }
+
+    pub(crate) fn tvd(&self, md: f64) -> f64 {
+        self.trajectory.tvd(md)
+    }
+
+    pub(crate) fn t(&self, md: f64) -> f64 {
+        self.profile.t(md, self.tvd(md))
+    }
+
+    // 测深 md 处的 cosθ（θ 为井斜角）
+    fn cos(&self, md: f64) -> f64 {
+        let (a, b) = ((md - 0.5).max(0.0), (md + 0.5).min(self.md_total));
+        if b > a {
+            (self.tvd(b) - self.tvd(a)) / (b - a)
+        } else {
+            1.0
+        }
+    }
+}
+
+// Cullender-Smith：L = ∫ I dp，I = (p/TZ)/[0.03415·rg·(p/TZ)²·cosθ + F]
+// 每步分两个半步：先用梯形公式求中点压力，再用 Simpson 公式
+// (p_out - p_in)(I_in + 4·I_mid + I_out)/6 = ΔL 求下端压力，均由牛顿迭代求解；
+// 每步的 cosθ 取该步垂深增量与测深增量之比
+fn cullender_smith(
+    flow: &Flow,
+    path: &Path,
+    md_in: f64,
+    md_out: f64,
+    p_in: f64,
+) -> Result<Solution, CalcError> {
+    let gas = flow.gas;
+    let integrand = |md: f64, cos: f64, p: f64| -> Result<f64, CalcError> {
+        let t = path.t(md);
+        let z = z_checked(gas.method, gas.pc, gas.tc, t, p)?.value;
+        let x = p / (t * z);
+        Ok(x / (0.03415 * gas.rg * x * x * cos + flow.friction(t, p, z)))
+    };
+    // 已知上端 (p0, w0)，求满足 (p - p0)·(w0 + I(p)) = c 的压力
+    let step = |md: f64, cos: f64, p0: f64, w0: f64, c: f64, guess: f64| {
+        let sol = Newton::new().tol(PRESSURE_TOL).solve(
+            |p| match integrand(md, cos, p) {
+                Ok(i) => (p - p0) * (w0 + i) - c,
+                Err(_) => f64::NAN,
+            },
+            guess,
+        );
+        check_converged(sol)
+    };
+
+    let steps = (((md_out - md_in) / MAX_DH).ceil() as usize).max(1);
+    let dl = (md_out - md_in) / steps as f64;
+    let mut p = p_in;
+    let mut result = Solution::exact(p_in);
+    for k in 0..steps {
+        let md0 = md_in + dl * k as f64;
+        let (md_mid, md_end) = (md0 + 0.5 * dl, md0 + dl);
+        let cos = if dl > 0.0 {
+            (path.tvd(md_end) - path.tvd(md0)) / dl
+        } else {
+            1.0
+        };
+        // 无流动的水平段压力不变
+        if flow.qg == 0.0 && cos.abs() < 1e-9 {
+            continue;
+        }
+        let i_in = integrand(md0, cos, p)?;
+        let mid = step(md_mid, cos, p, i_in, dl, p + 0.5 * dl / i_in)?;
+        let p_mid = check_pressure(mid.value)?;
+        let i_mid = integrand(md_mid, cos, p_mid)?;
+        let end = step(
+            md_end,
+            cos,
+            p,
+            i_in + 4.0 * i_mid,
+            6.0 * dl,
+            p_mid + 0.5 * dl / i_mid,
+        )?;
+        p = check_pressure(end.value)?;
+        result = Solution {
+            value: p,
+            iterations: result.iterations + mid.iterations + end.iterations,
+            residual: result
+                .residual
+                .abs()
+                .max(mid.residual.abs())
+                .max(end.residual.abs()),
+            converged: true,
+        };
+    }
+    Ok(result)
+}
 
-    pts
+// 平均温度和平均压缩系数法（斜井）：
+// pwf² = pts²·e^(2s) + F·(T̄Z̄)²/(0.03415·rg)·(L/H)·(e^(2s) - 1)，s = 0.03415·rg·H/(T̄Z̄)
+// H 为垂深增量，L 为测深增量；H → 0（水平段）时 (L/H)·(e^(2s) - 1) → 2·0.03415·rg·L/(T̄Z̄)
+fn average_tz(
+    flow: &Flow,
+    path: &Path,
+    md_in: f64,
+    md_out: f64,
+    p_in: f64,
+) -> Result<Solution, CalcError> {
+    let gas = flow.gas;
+    let t = (path.t(md_in) + path.t(md_out)) * 0.5;
+    let (l, h) = (md_out - md_in, path.tvd(md_out) - path.tvd(md_in));
+    let a = 0.03415 * gas.rg;
+    let p_out = |p_avg: f64| -> Result<f64, CalcError> {
+        let z = z_checked(gas.method, gas.pc, gas.tc, t, p_avg)?.value;
+        let f = flow.friction(t, p_avg, z);
+        let s = a * h / (t * z);
+        let e2s = (2.0 * s).exp();
+        let growth = if s.abs() > 1e-9 {
+            l / h * (e2s - 1.0)
+        } else {
+            2.0 * a * l / (t * z)
+        };
+        Ok((p_in.powi(2) * e2s + f * (t * z).powi(2) / a * growth).sqrt())
+    };
+
+    let sol = Newton::new().tol(PRESSURE_TOL).solve(
+        |p| match p_out((p_in + p) * 0.5) {
+            Ok(pw) => p - pw,
+            Err(_) => f64::NAN,
+        },
+        p_in + p_in * h / 12192.0,
+    );
+    check_converged(sol)
 }
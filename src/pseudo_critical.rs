@@ -0,0 +1,144 @@
+use serde::{Deserialize, Serialize};
+
+// 单位换算
+const PSI_TO_MPA: f64 = 0.00689476;
+const RANKINE_TO_K: f64 = 1.0 / 1.8;
+
+// 非烃组分临界参数（°R, psia）
+const TC_N2: f64 = 227.5;
+const PC_N2: f64 = 493.1;
+const TC_CO2: f64 = 547.9;
+const PC_CO2: f64 = 1071.0;
+const TC_H2S: f64 = 672.4;
+const PC_H2S: f64 = 1306.0;
+
+/// 由相对密度计算拟临界参数的方法
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PcMethod {
+    /// Standing 天然气（默认）
+    #[default]
+    Standing,
+    /// Standing 凝析气
+    StandingCondensate,
+    /// Sutton（1985）
+    Sutton,
+    /// Piper-McCain-Corredor（1993），直接计入 N2/CO2/H2S
+    PiperMccainCorredor,
+}
+
+/// 由气体相对密度和非烃组分摩尔分数计算拟临界参数
+/// 参数:
+///   method – 关联式
+///   rg     – 气体相对密度（空气=1）
+///   yn2    – N2 摩尔分数
+///   yco2   – CO2 摩尔分数
+///   yh2s   – H2S 摩尔分数
+/// 返回:
+///   (拟临界压力 MPa, 拟临界温度 K)
+pub fn pseudo_critical(method: PcMethod, rg: f64, yn2: f64, yco2: f64, yh2s: f64) -> (f64, f64) {
+    let (ppc, tpc) = match method {
+        PcMethod::PiperMccainCorredor => piper_mccain_corredor(rg, yn2, yco2, yh2s),
+        _ => {
+            // 扣除非烃组分得到烃类相对密度，再按 Kay 规则与非烃组分混合
+            let ynon = yn2 + yco2 + yh2s;
+            let rg_hc = if ynon < 1.0 {
+                (rg - 0.967 * yn2 - 1.52 * yco2 - 1.18 * yh2s) / (1.0 - ynon)
+            } else {
+                rg
+            };
+            let (ppc_hc, tpc_hc) = hydrocarbon(method, rg_hc);
+            let ppc = (1.0 - ynon) * ppc_hc + yn2 * PC_N2 + yco2 * PC_CO2 + yh2s * PC_H2S;
+            let tpc = (1.0 - ynon) * tpc_hc + yn2 * TC_N2 + yco2 * TC_CO2 + yh2s * TC_H2S;
+            (ppc, tpc)
+        }
+    };
+    (ppc * PSI_TO_MPA, tpc * RANKINE_TO_K)
+}
+
+// 烃类部分拟临界参数（psia, °R）
+fn hydrocarbon(method: PcMethod, rg: f64) -> (f64, f64) {
+    match method {
+        PcMethod::StandingCondensate => (
+            706.0 - 51.7 * rg - 11.1 * rg.powi(2),
+            187.0 + 330.0 * rg - 71.5 * rg.powi(2),
+        ),
+        PcMethod::Sutton => (
+            756.8 - 131.07 * rg - 3.6 * rg.powi(2),
+            169.2 + 349.5 * rg - 74.0 * rg.powi(2),
+        ),
+        _ => (
+            677.0 + 15.0 * rg - 37.5 * rg.powi(2),
+            168.0 + 325.0 * rg - 12.5 * rg.powi(2),
+        ),
+    }
+}
+
+// Piper-McCain-Corredor 法（psia, °R）
+fn piper_mccain_corredor(rg: f64, yn2: f64, yco2: f64, yh2s: f64) -> (f64, f64) {
+    let j = 0.11582
+        - 0.45820 * yh2s * (TC_H2S / PC_H2S)
+        - 0.90348 * yco2 * (TC_CO2 / PC_CO2)
+        - 0.66026 * yn2 * (TC_N2 / PC_N2)
+        + 0.70729 * rg
+        - 0.099397 * rg.powi(2);
+    let k = 3.8216
+        - 0.06534 * yh2s * (TC_H2S / PC_H2S.sqrt())
+        - 0.42113 * yco2 * (TC_CO2 / PC_CO2.sqrt())
+        - 0.91249 * yn2 * (TC_N2 / PC_N2.sqrt())
+        + 17.438 * rg
+        - 3.2191 * rg.powi(2);
+    let tpc = k.powi(2) / j;
+    (tpc / j, tpc)
+}
+
+/// 酸性气体拟临界参数校正方法
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SourCorrection {
+    /// 不校正（默认）
+    #[default]
+    None,
+    /// Wichert-Aziz
+    WichertAziz,
+    /// Carr-Kobayashi-Burrows
+    CarrKobayashiBurrows,
+}
+
+/// 酸性气体（H2S、CO2）拟临界参数校正
+/// 参数:
+///   method – 校正方法
+///   pc     – 拟临界压力，MPa
+///   tc     – 拟临界温度，K
+///   yn2    – N2 摩尔分数
+///   yco2   – CO2 摩尔分数
+///   yh2s   – H2S 摩尔分数
+/// 返回:
+///   (校正后拟临界压力 MPa, 校正后拟临界温度 K)
+pub fn sour_correct(
+    method: SourCorrection,
+    pc: f64,
+    tc: f64,
+    yn2: f64,
+    yco2: f64,
+    yh2s: f64,
+) -> (f64, f64) {
+    match method {
+        SourCorrection::None => (pc, tc),
+        SourCorrection::WichertAziz => {
+            let a = yco2 + yh2s;
+            let b = yh2s;
+            // ε 单位 °R，换算为 K
+            let e = (120.0 * (a.powf(0.9) - a.powf(1.6)) + 15.0 * (b.sqrt() - b.powi(4)))
+                * RANKINE_TO_K;
+            let tc_corr = tc - e;
+            let pc_corr = pc * tc_corr / (tc + b * (1.0 - b) * e);
+            (pc_corr, tc_corr)
+        }
+        SourCorrection::CarrKobayashiBurrows => {
+            let tc_corr = tc + (-80.0 * yco2 + 130.0 * yh2s - 250.0 * yn2) * RANKINE_TO_K;
+            let pc_corr = pc + (440.0 * yco2 + 600.0 * yh2s - 170.0 * yn2) * PSI_TO_MPA;
+            (pc_corr, tc_corr)
+        }
+    }
+}
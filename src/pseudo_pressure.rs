@@ -0,0 +1,161 @@
+use crate::handlers::calculate_viscosity;
+use crate::pressure::{self, CalcError};
+use crate::zfactor::ZMethod;
+
+// Simpson 积分的最大压力步长，MPa
+const MAX_DP: f64 = 0.05;
+
+/// 等温条件下的气体物性（拟压力、拟时间计算用）
+#[derive(Debug, Clone, Copy)]
+pub struct Gas {
+    pub method: ZMethod,
+    pub rg: f64,
+    pub pc: f64, // MPa
+    pub tc: f64, // K
+    pub t: f64,  // 地层温度，K
+    pub yn2: f64,
+    pub yco2: f64,
+    pub yh2s: f64,
+}
+
+impl Gas {
+    pub fn z(&self, p: f64) -> Result<f64, CalcError> {
+        Ok(pressure::z_checked(self.method, self.pc, self.tc, self.t, p)?.value)
+    }
+
+    /// 粘度，mPa·s
+    pub fn mu(&self, p: f64, z: f64) -> f64 {
+        let density = 3.4844 * p * self.rg / (z * self.t);
+        calculate_viscosity(self.rg, self.t, density, self.yn2, self.yco2, self.yh2s)
+    }
+
+    /// 被积函数 2p/(μz)，p = 0 时取极限值 0
+    fn integrand(&self, p: f64) -> Result<f64, CalcError> {
+        if p == 0.0 {
+            return Ok(0.0);
+        }
+        let z = self.z(p)?;
+        Ok(2.0 * p / (self.mu(p, z) * z))
+    }
+
+    /// 复化 Simpson 公式求 ∫[a,b] 2p/(μz) dp
+    fn integrate(&self, a: f64, b: f64) -> Result<f64, CalcError> {
+        let n = (((b - a).abs() / MAX_DP).ceil() as usize).max(1) * 2;
+        let h = (b - a) / n as f64;
+        let mut sum = self.integrand(a)? + self.integrand(b)?;
+        for i in 1..n {
+            let w = if i % 2 == 1 { 4.0 } else { 2.0 };
+            sum += w * self.integrand(a + h * i as f64)?;
+        }
+        Ok(sum * h / 3.0)
+    }
+}
+
+/// 拟压力表中的一行
+#[derive(Debug, Clone, Copy)]
+pub struct PseudoPressure {
+    pub p: f64,      // MPa
+    pub mu: f64,     // mPa·s
+    pub z: f64,      //
+    pub mp: f64,     // m(p)，MPa²/(mPa·s)
+    pub p_norm: f64, // 规整化拟压力，MPa
+}
+
+/// 计算拟压力 m(p) = 2∫[0,p] p/(μz) dp 及规整化拟压力（Meunier）
+/// pn = pref + (μz/p)ref·[m(p) - m(pref)]/2
+/// 参数:
+///   gas       – 气体物性
+///   pressures – 压力，MPa（可乱序，结果与输入顺序一致）
+///   p_ref     – 规整化参考压力（一般为原始地层压力），MPa
+pub fn table(gas: &Gas, pressures: &[f64], p_ref: f64) -> Result<Vec<PseudoPressure>, CalcError> {
+    for &p in pressures.iter().chain([&p_ref]) {
+        pressure::check_pressure(p)?;
+    }
+
+    // 按压力排序后分段累积积分
+    let mut points: Vec<f64> = pressures.iter().copied().chain([p_ref]).collect();
+    points.sort_by(f64::total_cmp);
+    let mut mps = Vec::with_capacity(points.len());
+    let mut last = (0.0, 0.0);
+    for &p in &points {
+        let mp = last.1 + gas.integrate(last.0, p)?;
+        mps.push((p, mp));
+        last = (p, mp);
+    }
+    let m_of = |p: f64| {
+        mps.iter()
+            .find(|(pi, _)| *pi == p)
+            .map_or(0.0, |&(_, mp)| mp)
+    };
+
+    let z_ref = gas.z(p_ref)?;
+    let ratio_ref = gas.mu(p_ref, z_ref) * z_ref / p_ref;
+    let m_ref = m_of(p_ref);
+
+    pressures
+        .iter()
+        .map(|&p| {
+            let z = gas.z(p)?;
+            let mp = m_of(p);
+            Ok(PseudoPressure {
+                p,
+                mu: gas.mu(p, z),
+                z,
+                mp,
+                p_norm: p_ref + ratio_ref * (mp - m_ref) / 2.0,
+            })
+        })
+        .collect()
+}
+
+/// 拟时间序列中的一点
+#[derive(Debug, Clone, Copy)]
+pub struct PseudoTime {
+    pub time: f64,    // 实际时间（与输入单位一致）
+    pub p: f64,       // MPa
+    pub mu: f64,      // mPa·s
+    pub cg: f64,      // 1/MPa
+    pub ta: f64,      // 拟时间 ∫dt/(μcg)
+    pub ta_norm: f64, // 规整化拟时间 (μcg)ref·ta
+}
+
+/// 按压力历史计算拟时间 ta = ∫dt/(μ·cg)（梯形积分，以第一个点为起点）
+/// 参数:
+///   gas     – 气体物性
+///   history – (时间, 压力 MPa)，时间须严格递增，否则返回 InvalidInput
+///   p_ref   – 规整化参考压力，MPa
+pub fn pseudo_time(
+    gas: &Gas,
+    history: &[(f64, f64)],
+    p_ref: f64,
+) -> Result<Vec<PseudoTime>, CalcError> {
+    let mu_cg = |p: f64| -> Result<(f64, f64), CalcError> {
+        let z = gas.z(pressure::check_pressure(p)?)?;
+        let cg = pressure::cg(gas.method, gas.pc, gas.tc, gas.t, p).value;
+        Ok((gas.mu(p, z), cg))
+    };
+    let (mu_ref, cg_ref) = mu_cg(p_ref)?;
+
+    let mut result: Vec<PseudoTime> = Vec::with_capacity(history.len());
+    for &(time, p) in history {
+        let (mu, cg) = mu_cg(p)?;
+        let ta = match result.last() {
+            Some(prev) if time <= prev.time => {
+                return Err(CalcError::InvalidInput("压力历史的时间须严格递增"));
+            }
+            Some(prev) => {
+                prev.ta + 0.5 * (time - prev.time) * (1.0 / (prev.mu * prev.cg) + 1.0 / (mu * cg))
+            }
+            None => 0.0,
+        };
+        result.push(PseudoTime {
+            time,
+            p,
+            mu,
+            cg,
+            ta,
+            ta_norm: mu_ref * cg_ref * ta,
+        });
+    }
+    Ok(result)
+}
@@ -0,0 +1,57 @@
+use crate::pressure::CalcError;
+use serde::Serialize;
+
+/// 一元线性回归 y = intercept + slope·x（最小二乘）
+#[derive(Serialize, Debug, Clone, Copy)]
+pub struct LinearFit {
+    pub slope: f64,
+    pub intercept: f64,
+    pub r2: f64,           // 决定系数
+    pub se_slope: f64,     // 斜率标准误差
+    pub se_intercept: f64, // 截距标准误差
+    pub n: usize,          // 参与回归的点数
+}
+
+impl LinearFit {
+    /// 拟合直线与 x 轴的交点
+    pub fn x_intercept(&self) -> f64 {
+        -self.intercept / self.slope
+    }
+}
+
+/// 最小二乘直线拟合，至少需要两个 x 不全相同的点
+pub fn linear_fit(x: &[f64], y: &[f64]) -> Result<LinearFit, CalcError> {
+    let n = x.len().min(y.len());
+    if n < 2 {
+        return Err(CalcError::InvalidInput("回归至少需要两个数据点"));
+    }
+    let (x, y) = (&x[..n], &y[..n]);
+    let nf = n as f64;
+    let mx = x.iter().sum::<f64>() / nf;
+    let my = y.iter().sum::<f64>() / nf;
+    let sxx: f64 = x.iter().map(|xi| (xi - mx).powi(2)).sum();
+    let syy: f64 = y.iter().map(|yi| (yi - my).powi(2)).sum();
+    let sxy: f64 = x.iter().zip(y).map(|(xi, yi)| (xi - mx) * (yi - my)).sum();
+    if sxx <= 0.0 || !sxx.is_finite() || !sxy.is_finite() {
+        return Err(CalcError::InvalidInput("回归数据点的自变量不能全部相同"));
+    }
+
+    let slope = sxy / sxx;
+    let intercept = my - slope * mx;
+    let sse: f64 = x
+        .iter()
+        .zip(y)
+        .map(|(xi, yi)| (yi - intercept - slope * xi).powi(2))
+        .sum();
+    let r2 = if syy > 0.0 { 1.0 - sse / syy } else { 1.0 };
+    // 两点时残差自由度为 0，标准误差记为 0
+    let s2 = if n > 2 { sse / (nf - 2.0) } else { 0.0 };
+    Ok(LinearFit {
+        slope,
+        intercept,
+        r2,
+        se_slope: (s2 / sxx).sqrt(),
+        se_intercept: (s2 * (1.0 / nf + mx * mx / sxx)).sqrt(),
+        n,
+    })
+}
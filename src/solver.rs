@@ -0,0 +1,184 @@
+use serde::Serialize;
+
+/// 迭代求解结果
+#[derive(Serialize, Debug, Clone, Copy, PartialEq)]
+pub struct Solution {
+    pub value: f64,      // 解
+    pub iterations: u32, // 迭代次数
+    pub residual: f64,   // 终止时残差
+    pub converged: bool, // 是否收敛
+}
+
+impl Solution {
+    /// 显式公式的结果（无需迭代）
+    pub fn exact(value: f64) -> Self {
+        Solution {
+            value,
+            iterations: 0,
+            residual: 0.0,
+            converged: value.is_finite(),
+        }
+    }
+
+    /// 对解做变换，保留迭代信息
+    pub fn map(self, f: impl FnOnce(f64) -> f64) -> Self {
+        let value = f(self.value);
+        Solution {
+            value,
+            converged: self.converged && value.is_finite(),
+            ..self
+        }
+    }
+}
+
+// 阻尼因子下限
+const MIN_DAMPING: f64 = 1.0 / 64.0;
+
+/// 带阻尼和区间保护的牛顿迭代
+/// 收敛判据：|f(x)| ≤ tol，或步长 ≤ tol·max(1, |x|)
+/// 步长使残差增大时逐次减半（阻尼）；给定有根区间时，
+/// 牛顿步越出区间或导数失效则改用二分
+#[derive(Debug, Clone, Copy)]
+pub struct Newton {
+    tol: f64,
+    max_iter: u32,
+    bracket: Option<(f64, f64)>,
+}
+
+impl Default for Newton {
+    fn default() -> Self {
+        Newton {
+            tol: 1e-10,
+            max_iter: 100,
+            bracket: None,
+        }
+    }
+}
+
+impl Newton {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 收敛容差
+    pub fn tol(mut self, tol: f64) -> Self {
+        self.tol = tol;
+        self
+    }
+
+    /// 解所在区间 [lo, hi]
+    pub fn bracket(mut self, lo: f64, hi: f64) -> Self {
+        self.bracket = Some((lo.min(hi), lo.max(hi)));
+        self
+    }
+
+    /// 求 f(x) = 0，导数用中心差分
+    pub fn solve(&self, f: impl Fn(f64) -> f64, x0: f64) -> Solution {
+        self.solve_with_derivative(
+            |x| {
+                let h = (x.abs() * 1e-7).max(1e-10);
+                (f(x), (f(x + h) - f(x - h)) / (2.0 * h))
+            },
+            x0,
+        )
+    }
+
+    /// 求 f(x) = 0，fdf 返回 (f(x), f'(x))
+    pub fn solve_with_derivative(&self, fdf: impl Fn(f64) -> (f64, f64), x0: f64) -> Solution {
+        // 仅当区间两端异号时才作为有根区间使用，否则只作为取值范围
+        let mut bracket = self.bracket.map(|(lo, hi)| {
+            let (flo, fhi) = (fdf(lo).0, fdf(hi).0);
+            let signed = flo.is_finite() && fhi.is_finite() && flo * fhi <= 0.0;
+            (lo, hi, flo, signed)
+        });
+
+        let mut x = match bracket {
+            Some((lo, hi, ..)) if !(lo..=hi).contains(&x0) => 0.5 * (lo + hi),
+            _ => x0,
+        };
+        let (mut fx, mut dfx) = fdf(x);
+
+        for iter in 1..=self.max_iter {
+            if !fx.is_finite() {
+                return Solution {
+                    value: x,
+                    iterations: iter - 1,
+                    residual: fx,
+                    converged: false,
+                };
+            }
+            if fx.abs() <= self.tol {
+                return Solution {
+                    value: x,
+                    iterations: iter - 1,
+                    residual: fx,
+                    converged: true,
+                };
+            }
+
+            // 缩小有根区间
+            if let Some((lo, hi, flo, true)) = bracket {
+                bracket = Some(if flo * fx < 0.0 {
+                    (lo, x, flo, true)
+                } else {
+                    (x, hi, fx, true)
+                });
+            }
+
+            let step = fx / dfx;
+            let usable = step.is_finite() && dfx != 0.0;
+            let newton = x - step;
+
+            // 牛顿步不可用或越出区间时：有根区间取中点，否则截断到区间内
+            let (next, bisect) = match bracket {
+                Some((lo, hi, _, signed)) if !usable || newton <= lo || newton >= hi => {
+                    if signed || !usable {
+                        (0.5 * (lo + hi), true)
+                    } else {
+                        (newton.clamp(lo, hi), false)
+                    }
+                }
+                _ if !usable => {
+                    return Solution {
+                        value: x,
+                        iterations: iter,
+                        residual: fx,
+                        converged: false,
+                    };
+                }
+                _ => (newton, false),
+            };
+
+            // 阻尼：牛顿步未使残差减小时步长逐次减半
+            let full = next - x;
+            let mut trial = next;
+            let (mut ft, mut dft) = fdf(trial);
+            let mut lambda = 1.0;
+            while !(bisect || ft.is_finite() && ft.abs() < fx.abs()) && lambda > MIN_DAMPING {
+                lambda *= 0.5;
+                trial = x + lambda * full;
+                (ft, dft) = fdf(trial);
+            }
+
+            x = trial;
+            fx = ft;
+            dfx = dft;
+
+            if full.abs() <= self.tol * x.abs().max(1.0) && fx.is_finite() {
+                return Solution {
+                    value: x,
+                    iterations: iter,
+                    residual: fx,
+                    converged: true,
+                };
+            }
+        }
+
+        Solution {
+            value: x,
+            iterations: self.max_iter,
+            residual: fx,
+            converged: fx.is_finite() && fx.abs() <= self.tol,
+        }
+    }
+}
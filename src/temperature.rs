@@ -0,0 +1,154 @@
+use serde::Deserialize;
+use std::f64::consts::PI;
+
+// 标准状况（20 ℃、0.101325 MPa）下空气密度，kg/m³
+const AIR_DENSITY_SC: f64 = 1.205;
+// 重力加速度，m/s²
+const G: f64 = 9.81;
+
+/// 井筒传热参数（Ramey、Hasan-Kabir 模型）
+#[derive(Deserialize, Debug, Clone, Copy)]
+#[serde(default)]
+pub struct HeatTransfer {
+    pub time: f64,  // 生产时间，d
+    pub u: f64,     // 总传热系数，W/(m²·K)，以 r 为基准
+    pub r: f64,     // 传热系数基准半径（油管外半径），m
+    pub rw: f64,    // 井眼半径，m
+    pub ke: f64,    // 地层导热系数，W/(m·K)
+    pub alpha: f64, // 地层热扩散系数，m²/s
+    pub cp: f64,    // 气体比定压热容，J/(kg·K)
+    pub phi: f64,   // 焦耳-汤姆逊及动能修正项，K/m（仅 Hasan-Kabir）
+}
+
+impl Default for HeatTransfer {
+    fn default() -> Self {
+        HeatTransfer {
+            time: 30.0,
+            u: 30.0,
+            r: 0.0365,
+            rw: 0.108,
+            ke: 2.0,
+            alpha: 1.0e-6,
+            cp: 2400.0,
+            phi: 0.0,
+        }
+    }
+}
+
+/// 井筒温度模型
+/// linear：井温在 tts～tws 间沿垂深线性分布；
+/// ramey、hasan_kabir：tts 为地表恒温层温度、tws 为井底（地层）温度，
+/// 地温沿垂深线性分布，流体温度按产出过程中的传热计算
+#[derive(Deserialize, Debug, Clone, Copy, Default)]
+#[serde(tag = "model", rename_all = "snake_case")]
+pub enum TemperatureModel {
+    #[default]
+    Linear,
+    /// Ramey（1962）瞬态传热
+    Ramey(HeatTransfer),
+    /// Hasan-Kabir（1991）流动温度
+    HasanKabir(HeatTransfer),
+}
+
+impl TemperatureModel {
+    pub fn name(&self) -> &'static str {
+        match self {
+            TemperatureModel::Linear => "线性分布",
+            TemperatureModel::Ramey(_) => "Ramey",
+            TemperatureModel::HasanKabir(_) => "Hasan-Kabir",
+        }
+    }
+}
+
+/// 井筒温度剖面
+#[derive(Debug, Clone, Copy)]
+pub struct Profile {
+    tts: f64,
+    tws: f64,
+    md_total: f64,
+    tvd_total: f64,
+    relax: f64,  // 松弛距离 A，m
+    excess: f64, // 流体温度与地温差的渐近值，K
+}
+
+impl Profile {
+    /// 参数:
+    ///   model     – 温度模型
+    ///   tts       – 井口（linear）或地表恒温层温度，K
+    ///   tws       – 井底温度，K
+    ///   md_total  – 井底测深，m
+    ///   tvd_total – 井底垂深，m
+    ///   rg        – 气体相对密度
+    ///   qg        – 产气量，m³/d（标准状况）
+    pub fn new(
+        model: &TemperatureModel,
+        tts: f64,
+        tws: f64,
+        md_total: f64,
+        tvd_total: f64,
+        rg: f64,
+        qg: f64,
+    ) -> Self {
+        let mut profile = Profile {
+            tts,
+            tws,
+            md_total,
+            tvd_total,
+            relax: 0.0,
+            excess: 0.0,
+        };
+        let (ht, f) = match model {
+            TemperatureModel::Linear => return profile,
+            TemperatureModel::Ramey(ht) => (ht, ramey_time_function(ht)),
+            TemperatureModel::HasanKabir(ht) => (ht, hasan_kabir_time_function(ht)),
+        };
+        if qg <= 0.0 || md_total <= 0.0 {
+            return profile;
+        }
+
+        // 质量流量，kg/s
+        let w = qg * AIR_DENSITY_SC * rg / 86400.0;
+        // A = w·cp·(ke + r·U·f(t))/(2π·r·U·ke)
+        profile.relax = w * ht.cp * (ht.ke + ht.r * ht.u * f) / (2.0 * PI * ht.r * ht.u * ht.ke);
+        // 沿井筒的平均地温梯度（斜井按垂深/测深折算），K/m
+        let sin = tvd_total / md_total;
+        let gradient = (tws - tts) / md_total;
+        profile.excess = match model {
+            TemperatureModel::HasanKabir(_) => gradient - G * sin / ht.cp + ht.phi,
+            _ => gradient,
+        } * profile.relax;
+        profile
+    }
+
+    /// 地温，K
+    fn earth(&self, tvd: f64) -> f64 {
+        if self.tvd_total > 0.0 {
+            self.tts + (self.tws - self.tts) * tvd / self.tvd_total
+        } else {
+            self.tts
+        }
+    }
+
+    /// 测深 md（垂深 tvd）处的井筒流体温度，K
+    /// T = Te + (1 - e^(-x/A))·excess，x 为距井底的测深
+    pub fn t(&self, md: f64, tvd: f64) -> f64 {
+        let te = self.earth(tvd);
+        if self.relax <= 0.0 {
+            return te;
+        }
+        let x = (self.md_total - md).max(0.0);
+        te + (1.0 - (-x / self.relax).exp()) * self.excess
+    }
+}
+
+// Ramey 长时间近似：f(t) = -ln(rw/(2√(αt))) - 0.290
+fn ramey_time_function(ht: &HeatTransfer) -> f64 {
+    let t = ht.time * 86400.0;
+    (-(ht.rw / (2.0 * (ht.alpha * t).sqrt())).ln() - 0.290).max(0.0)
+}
+
+// Hasan-Kabir：TD = ln[e^(-0.2tD) + (1.5 - 0.3719·e^(-tD))·√tD]，tD = αt/rw²
+fn hasan_kabir_time_function(ht: &HeatTransfer) -> f64 {
+    let td = ht.alpha * ht.time * 86400.0 / ht.rw.powi(2);
+    ((-0.2 * td).exp() + (1.5 - 0.3719 * (-td).exp()) * td.sqrt()).ln()
+}
@@ -0,0 +1,121 @@
+use crate::pressure::CalcError;
+use serde::{Deserialize, Serialize};
+
+// 狗腿度的计量长度，m
+const DLS_LENGTH: f64 = 30.0;
+
+/// 测斜点
+#[derive(Deserialize, Serialize, Debug, Clone, Copy)]
+pub struct SurveyStation {
+    pub md: f64,  // 测深，m
+    pub inc: f64, // 井斜角，°
+    pub azi: f64, // 方位角，°
+}
+
+/// 井眼轨迹上的一点（最小曲率法计算结果）
+#[derive(Serialize, Debug, Clone, Copy)]
+pub struct TrajectoryPoint {
+    pub md: f64,    // 测深，m
+    pub inc: f64,   // 井斜角，°
+    pub azi: f64,   // 方位角，°
+    pub tvd: f64,   // 垂深，m
+    pub north: f64, // 北向位移，m
+    pub east: f64,  // 东向位移，m
+    pub dls: f64,   // 狗腿度，°/30m
+}
+
+/// 井眼轨迹，无测斜数据时为直井（垂深 = 测深）
+#[derive(Debug, Clone, Default)]
+pub struct Trajectory {
+    points: Vec<TrajectoryPoint>,
+}
+
+impl Trajectory {
+    /// 直井
+    pub fn vertical() -> Self {
+        Self::default()
+    }
+
+    /// 由测斜数据按最小曲率法计算井眼轨迹
+    /// 第一个测点测深大于 0 时，自井口至该点按直井处理
+    pub fn minimum_curvature(stations: &[SurveyStation]) -> Result<Self, CalcError> {
+        let mut points: Vec<TrajectoryPoint> = Vec::with_capacity(stations.len() + 1);
+        let tie_in = TrajectoryPoint {
+            md: 0.0,
+            inc: 0.0,
+            azi: 0.0,
+            tvd: 0.0,
+            north: 0.0,
+            east: 0.0,
+            dls: 0.0,
+        };
+
+        for s in stations {
+            if !(0.0..=180.0).contains(&s.inc) {
+                return Err(CalcError::InvalidInput("井斜角须在 0～180° 之间"));
+            }
+            let prev = points.last().copied().unwrap_or(tie_in);
+            let dmd = s.md - prev.md;
+            if dmd < 0.0 || (dmd == 0.0 && !points.is_empty()) {
+                return Err(CalcError::InvalidInput("测斜点测深须递增"));
+            }
+            if dmd == 0.0 {
+                // 井口处的测点
+                points.push(TrajectoryPoint {
+                    inc: s.inc,
+                    azi: s.azi,
+                    ..tie_in
+                });
+                continue;
+            }
+
+            let (i1, a1) = (prev.inc.to_radians(), prev.azi.to_radians());
+            let (i2, a2) = (s.inc.to_radians(), s.azi.to_radians());
+            // 狗腿角与比例因子 RF = 2/β·tan(β/2)
+            let cos_beta = (i2 - i1).cos() - i1.sin() * i2.sin() * (1.0 - (a2 - a1).cos());
+            let beta = cos_beta.clamp(-1.0, 1.0).acos();
+            let rf = if beta < 1e-8 {
+                1.0
+            } else {
+                2.0 / beta * (beta / 2.0).tan()
+            };
+            let half = dmd / 2.0 * rf;
+
+            points.push(TrajectoryPoint {
+                md: s.md,
+                inc: s.inc,
+                azi: s.azi,
+                tvd: prev.tvd + half * (i1.cos() + i2.cos()),
+                north: prev.north + half * (i1.sin() * a1.cos() + i2.sin() * a2.cos()),
+                east: prev.east + half * (i1.sin() * a1.sin() + i2.sin() * a2.sin()),
+                dls: beta.to_degrees() * DLS_LENGTH / dmd,
+            });
+        }
+
+        if points.first().is_some_and(|p| p.md > 0.0) {
+            points.insert(0, tie_in);
+        }
+        Ok(Trajectory { points })
+    }
+
+    pub fn points(&self) -> &[TrajectoryPoint] {
+        &self.points
+    }
+
+    /// 测深 md 处的垂深，m
+    /// 测点之间线性插值，最后一个测点以下按该点井斜角外推
+    pub fn tvd(&self, md: f64) -> f64 {
+        let Some(last) = self.points.last() else {
+            return md;
+        };
+        if md >= last.md {
+            return last.tvd + (md - last.md) * last.inc.to_radians().cos();
+        }
+        let k = self.points.partition_point(|p| p.md <= md);
+        if k == 0 {
+            return md;
+        }
+        let (a, b) = (&self.points[k - 1], &self.points[k]);
+        a.tvd + (b.tvd - a.tvd) * (md - a.md) / (b.md - a.md)
+    }
+}
@@ -0,0 +1,279 @@
+use crate::solver::{Newton, Solution};
+use serde::{Deserialize, Serialize};
+
+/// 偏差因子（Z 因子）关联式
+/// 所有关联式均以对比压力 ppr、对比温度 tpr 为输入
+pub trait ZFactorCorrelation: Sync {
+    /// 关联式名称（用于结果回显）
+    fn name(&self) -> &'static str;
+
+    /// 适用的最低对比温度
+    fn min_tpr(&self) -> f64 {
+        1.0
+    }
+
+    /// 计算偏差因子 z
+    fn z(&self, ppr: f64, tpr: f64) -> f64 {
+        self.solve(ppr, tpr).value
+    }
+
+    /// 计算偏差因子 z，并返回迭代次数、残差和收敛标志
+    /// 显式公式返回 Solution::exact
+    fn solve(&self, ppr: f64, tpr: f64) -> Solution;
+
+    /// 计算对比压缩系数 cpr = 1/ppr - (1/z)(dz/dppr)
+    /// 默认采用中心差分求 dz/dppr，有解析导数的关联式可覆盖
+    fn cpr(&self, ppr: f64, tpr: f64) -> f64 {
+        let dp = (ppr * 1e-4).max(1e-6);
+        let z = self.z(ppr, tpr);
+        let dzdp = (self.z(ppr + dp, tpr) - self.z((ppr - dp).max(1e-8), tpr))
+            / (ppr + dp - (ppr - dp).max(1e-8));
+        1.0 / ppr - dzdp / z
+    }
+}
+
+/// 请求中可选的 Z 因子计算方法
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ZMethod {
+    /// Dranchuk-Purvis-Robinson（默认）
+    #[default]
+    Dpr,
+    /// Dranchuk-Abou-Kassem 11 常数
+    Dak,
+    /// Hall-Yarborough
+    HallYarborough,
+    /// Papay 显式公式
+    Papay,
+    /// Brill-Beggs 显式公式
+    BrillBeggs,
+}
+
+impl ZMethod {
+    pub fn correlation(self) -> &'static dyn ZFactorCorrelation {
+        match self {
+            ZMethod::Dpr => &Dpr,
+            ZMethod::Dak => &Dak,
+            ZMethod::HallYarborough => &HallYarborough,
+            ZMethod::Papay => &Papay,
+            ZMethod::BrillBeggs => &BrillBeggs,
+        }
+    }
+}
+
+// ============ Dranchuk-Purvis-Robinson ============
+const DPR_A1: f64 = 0.31506237;
+const DPR_A2: f64 = -1.0467099;
+const DPR_A3: f64 = -0.57832729;
+const DPR_A4: f64 = 0.53530771;
+const DPR_A5: f64 = -0.61232032;
+const DPR_A6: f64 = -0.10488813;
+const DPR_A7: f64 = 0.68157001;
+const DPR_A8: f64 = 0.68446549;
+
+pub struct Dpr;
+
+impl Dpr {
+    /// 牛顿迭代求对比密度 ρpr
+    fn luopr(ppr: f64, tpr: f64) -> Solution {
+        let c1 = DPR_A1 + DPR_A2 / tpr + DPR_A3 / tpr.powi(3);
+        let c2 = DPR_A4 + DPR_A5 / tpr;
+        Newton::new().bracket(0.0, 3.0).solve_with_derivative(
+            |luopr| {
+                let e = (-DPR_A8 * luopr.powi(2)).exp();
+                let fl = luopr - (0.27 * ppr) / tpr
+                    + c1 * luopr.powi(2)
+                    + c2 * luopr.powi(3)
+                    + (DPR_A5 * DPR_A6 * luopr.powi(6)) / tpr
+                    + (DPR_A7 * luopr.powi(3) / tpr.powi(3)) * (1.0 + DPR_A8 * luopr.powi(2)) * e;
+
+                let dfl = 1.0
+                    + c1 * (2.0 * luopr)
+                    + c2 * (3.0 * luopr.powi(2))
+                    + (DPR_A5 * DPR_A6 / tpr) * (6.0 * luopr.powi(5))
+                    + (DPR_A7 / tpr.powi(3))
+                        * (3.0 * luopr.powi(2) + 3.0 * DPR_A8 * luopr.powi(4)
+                            - 2.0 * DPR_A8.powi(2) * luopr.powi(6))
+                        * e;
+                (fl, dfl)
+            },
+            0.27 * ppr / tpr,
+        )
+    }
+}
+
+impl ZFactorCorrelation for Dpr {
+    fn name(&self) -> &'static str {
+        "Dranchuk-Purvis-Robinson"
+    }
+
+    fn min_tpr(&self) -> f64 {
+        1.05
+    }
+
+    fn solve(&self, ppr: f64, tpr: f64) -> Solution {
+        Self::luopr(ppr, tpr).map(|luopr| 0.27 * ppr / (luopr * tpr))
+    }
+
+    fn cpr(&self, ppr: f64, tpr: f64) -> f64 {
+        let luopr = Self::luopr(ppr, tpr).value;
+        let z = 0.27 * ppr / (luopr * tpr);
+
+        // dz/dρpr
+        let dzlt = (DPR_A1 + DPR_A2 / tpr + DPR_A3 / tpr.powi(3))
+            + 2.0 * (DPR_A4 + DPR_A5 / tpr) * luopr
+            + 5.0 * DPR_A5 * DPR_A6 * luopr.powi(4) / tpr
+            + (2.0 * DPR_A7 * luopr / tpr.powi(3))
+                * (1.0 + DPR_A8 * luopr.powi(2) - DPR_A8.powi(2) * luopr.powi(4))
+                * (-DPR_A8 * luopr.powi(2)).exp();
+
+        1.0 / ppr - (0.27 / (z.powi(2) * tpr)) * (dzlt / (1.0 + luopr * dzlt / z))
+    }
+}
+
+// ============ Dranchuk-Abou-Kassem ============
+const DAK_A1: f64 = 0.3265;
+const DAK_A2: f64 = -1.0700;
+const DAK_A3: f64 = -0.5339;
+const DAK_A4: f64 = 0.01569;
+const DAK_A5: f64 = -0.05165;
+const DAK_A6: f64 = 0.5475;
+const DAK_A7: f64 = -0.7361;
+const DAK_A8: f64 = 0.1844;
+const DAK_A9: f64 = 0.1056;
+const DAK_A10: f64 = 0.6134;
+const DAK_A11: f64 = 0.7210;
+
+pub struct Dak;
+
+impl ZFactorCorrelation for Dak {
+    fn name(&self) -> &'static str {
+        "Dranchuk-Abou-Kassem"
+    }
+
+    fn solve(&self, ppr: f64, tpr: f64) -> Solution {
+        let c1 = DAK_A1
+            + DAK_A2 / tpr
+            + DAK_A3 / tpr.powi(3)
+            + DAK_A4 / tpr.powi(4)
+            + DAK_A5 / tpr.powi(5);
+        let c2 = DAK_A6 + DAK_A7 / tpr + DAK_A8 / tpr.powi(2);
+        let c3 = DAK_A9 * (DAK_A7 / tpr + DAK_A8 / tpr.powi(2));
+        let c4 = DAK_A10 / tpr.powi(3);
+
+        // f(ρ) = ρ·z(ρ) - 0.27·ppr/tpr = 0
+        Newton::new()
+            .bracket(0.0, 3.0)
+            .solve_with_derivative(
+                |luopr| {
+                    let e = (-DAK_A11 * luopr.powi(2)).exp();
+                    let f = luopr + c1 * luopr.powi(2) + c2 * luopr.powi(3) - c3 * luopr.powi(6)
+                        + c4 * (1.0 + DAK_A11 * luopr.powi(2)) * luopr.powi(3) * e
+                        - 0.27 * ppr / tpr;
+                    let df = 1.0 + 2.0 * c1 * luopr + 3.0 * c2 * luopr.powi(2)
+                        - 6.0 * c3 * luopr.powi(5)
+                        + c4 * luopr.powi(2)
+                            * (3.0 + 3.0 * DAK_A11 * luopr.powi(2)
+                                - 2.0 * DAK_A11.powi(2) * luopr.powi(4))
+                            * e;
+                    (f, df)
+                },
+                0.27 * ppr / tpr,
+            )
+            .map(|luopr| 0.27 * ppr / (luopr * tpr))
+    }
+}
+
+// ============ Hall-Yarborough ============
+pub struct HallYarborough;
+
+impl ZFactorCorrelation for HallYarborough {
+    fn name(&self) -> &'static str {
+        "Hall-Yarborough"
+    }
+
+    fn solve(&self, ppr: f64, tpr: f64) -> Solution {
+        let tt = 1.0 / tpr;
+        let a = 0.06125 * ppr * tt * (-1.2 * (1.0 - tt).powi(2)).exp();
+        Newton::new()
+            .bracket(0.0, 0.99)
+            .solve_with_derivative(
+                |yy| {
+                    let fh = -a + (yy + yy.powi(2) + yy.powi(3) - yy.powi(4)) / (1.0 - yy).powi(3)
+                        - (14.76 * tt - 9.76 * tt.powi(2) + 4.58 * tt.powi(3)) * yy.powi(2)
+                        + (90.7 * tt - 242.2 * tt.powi(2) + 42.4 * tt.powi(3))
+                            * yy.powf(2.18 + 2.82 * tt);
+
+                    let dh = (1.0 + 4.0 * yy + 4.0 * yy.powi(2) - 4.0 * yy.powi(3) + yy.powi(4))
+                        / (1.0 - yy).powi(4)
+                        - (29.52 * tt - 19.52 * tt.powi(2) + 9.16 * tt.powi(3)) * yy
+                        + (2.18 + 2.82 * tt)
+                            * (90.7 * tt - 242.2 * tt.powi(2) + 42.4 * tt.powi(3))
+                            * yy.powf(1.18 + 2.82 * tt);
+                    (fh, dh)
+                },
+                a,
+            )
+            .map(|yy| a / yy)
+    }
+}
+
+// ============ Papay ============
+pub struct Papay;
+
+impl ZFactorCorrelation for Papay {
+    fn name(&self) -> &'static str {
+        "Papay"
+    }
+
+    fn min_tpr(&self) -> f64 {
+        1.2
+    }
+
+    fn solve(&self, ppr: f64, tpr: f64) -> Solution {
+        Solution::exact(
+            1.0 - 3.53 * ppr / 10f64.powf(0.9813 * tpr)
+                + 0.274 * ppr.powi(2) / 10f64.powf(0.8157 * tpr),
+        )
+    }
+}
+
+// ============ Brill-Beggs ============
+pub struct BrillBeggs;
+
+impl ZFactorCorrelation for BrillBeggs {
+    fn name(&self) -> &'static str {
+        "Brill-Beggs"
+    }
+
+    fn min_tpr(&self) -> f64 {
+        1.2
+    }
+
+    fn solve(&self, ppr: f64, tpr: f64) -> Solution {
+        let a = 1.39 * (tpr - 0.92).sqrt() - 0.36 * tpr - 0.101;
+        let b = (0.62 - 0.23 * tpr) * ppr
+            + (0.066 / (tpr - 0.86) - 0.037) * ppr.powi(2)
+            + 0.32 * ppr.powi(6) / 10f64.powf(9.0 * (tpr - 1.0));
+        let c = 0.132 - 0.32 * tpr.log10();
+        let d = 10f64.powf(0.3106 - 0.49 * tpr + 0.1824 * tpr.powi(2));
+        Solution::exact(a + (1.0 - a) * (-b).exp() + c * ppr.powf(d))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn papay_matches_published_correlation() {
+        // Papay (1968)：Z = 1 - 3.53·Ppr/10^(0.9813·Tpr) + 0.274·Ppr²/10^(0.8157·Tpr)
+        for (ppr, tpr, expected) in [(2.0, 1.5, 0.827351), (1.0, 2.0, 0.967928)] {
+            let z = Papay.solve(ppr, tpr).value;
+            assert!(
+                (z - expected).abs() < 1e-6,
+                "ppr = {ppr}, tpr = {tpr}, z = {z}"
+            );
+        }
+    }
+}